@@ -55,3 +55,19 @@ impl BytesDelta {
         Self::from_bytes(str.len())
     }
 }
+
+impl std::ops::Add for BytesDelta {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl std::ops::Sub for BytesDelta {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
@@ -1,7 +1,8 @@
 use super::{Anchor, Nodes};
 use crate::{
     measured::{IntoMeasured, Meter, Metrics},
-    MeasuredSequence, PeekableSequence, RewindSequence, Sequence, SequenceSegment,
+    MeasuredSequence, PeekableSequence, RecognizeSequence, RewindSequence, Sequence,
+    SequenceSegment,
 };
 
 impl<'a, T> Sequence for &'a [T] {
@@ -138,3 +139,32 @@ impl<'me, T, M: Metrics<[T]>> MeasuredSequence for Measured<'me, T, M> {
         self.meter.metrics()
     }
 }
+
+impl<'a, T> RecognizeSequence for &'a [T] {
+    type Recorder = Self;
+    type Mark = Self;
+    type Span = &'a [T];
+
+    fn record(self) -> (Self::Recorder, Self::Mark) {
+        (self, self)
+    }
+
+    fn recognize(recorder: Self::Recorder, mark: Self::Mark) -> (Self::Span, Self) {
+        (&mark[..mark.len() - recorder.len()], recorder)
+    }
+}
+
+impl<'me, T, M: Metrics<[T]>> RecognizeSequence for Measured<'me, T, M> {
+    type Recorder = Self;
+    type Mark = &'me [T];
+    type Span = &'me [T];
+
+    fn record(self) -> (Self::Recorder, Self::Mark) {
+        let base = self.base;
+        (self, base)
+    }
+
+    fn recognize(recorder: Self::Recorder, mark: Self::Mark) -> (Self::Span, Self) {
+        (&mark[..mark.len() - recorder.base.len()], recorder)
+    }
+}
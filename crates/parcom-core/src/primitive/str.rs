@@ -1,7 +1,8 @@
 use super::{Anchor, BytesDelta, Nodes};
 use crate::{
     measured::{IntoMeasured, Meter, Metrics},
-    MeasuredSequence, PeekableSequence, RewindSequence, Sequence, SequenceSegment,
+    MeasuredSequence, PeekableSequence, RecognizeSequence, RewindSequence, Sequence,
+    SequenceSegment,
 };
 
 impl<'a> Sequence for &'a str {
@@ -140,3 +141,32 @@ impl SequenceSegment for str {
         str::split_at(&self, mid.0)
     }
 }
+
+impl<'a> RecognizeSequence for &'a str {
+    type Recorder = Self;
+    type Mark = Self;
+    type Span = &'a str;
+
+    fn record(self) -> (Self::Recorder, Self::Mark) {
+        (self, self)
+    }
+
+    fn recognize(recorder: Self::Recorder, mark: Self::Mark) -> (Self::Span, Self) {
+        (&mark[..mark.len() - recorder.len()], recorder)
+    }
+}
+
+impl<'me, M: Metrics<str>> RecognizeSequence for Measured<'me, M> {
+    type Recorder = Self;
+    type Mark = &'me str;
+    type Span = &'me str;
+
+    fn record(self) -> (Self::Recorder, Self::Mark) {
+        let base = self.base;
+        (self, base)
+    }
+
+    fn recognize(recorder: Self::Recorder, mark: Self::Mark) -> (Self::Span, Self) {
+        (&mark[..mark.len() - recorder.base.len()], recorder)
+    }
+}
@@ -1,13 +1,23 @@
 mod segment;
 mod segment_stream;
 
+pub mod bits;
+pub mod chain;
+pub mod map;
 pub mod measured;
+pub mod recognize;
+pub mod take;
 
 use std::future::{Future, IntoFuture};
 
+pub use bits::{BitOrder, BitSequence};
+pub use chain::{chain, Chain};
+pub use map::{Mapped, SegmentMap};
 pub use measured::MeasuredSequence;
+pub use recognize::{RecognizeSequence, RecordSegment, Recording};
 pub use segment::SequenceSegment;
 pub use segment_stream::SegmentStream;
+pub use take::{take, Take};
 
 // lengthはsequencesegment必須とする。また、要素はCopyを要求する。
 pub trait Sequence: Sized {
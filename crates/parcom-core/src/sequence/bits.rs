@@ -0,0 +1,321 @@
+use crate::{measured::Metrics, MeasuredSequence, RewindSequence, SegmentStream, Sequence};
+use std::{
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// order of bits in a byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// the most significant bit comes first, e.g. H.264.
+    MsbFirst,
+    /// the least significant bit comes first, e.g. DEFLATE.
+    LsbFirst,
+}
+
+/// sequence of the bits of a byte sequence.
+///
+/// lengths are counted in bits. a bit position inside a byte is kept as `offset`.
+#[derive(Debug)]
+pub struct BitSequence<S> {
+    inner: S,
+    offset: usize,
+    order: BitOrder,
+}
+
+impl<S> BitSequence<S> {
+    pub fn new(inner: S, order: BitOrder) -> Self {
+        Self {
+            inner,
+            offset: 0,
+            order,
+        }
+    }
+
+    /// return the number of bits already read from the current byte.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// return the underlying sequence positioned at the current byte, including its read bits.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Sequence<Segment = [u8], Length = usize>> Sequence for BitSequence<S> {
+    type Length = usize;
+    type Segment = [bool];
+    type Segments<'a>
+        = BitSegments<'a, S>
+    where
+        Self: 'a;
+    type Advance = BitAdvance<S>;
+
+    fn segments(&mut self) -> Self::Segments<'_> {
+        BitSegments {
+            inner: &mut self.inner,
+            offset: self.offset,
+            order: self.order,
+            bytes: Vec::new(),
+            pos: 0,
+            fetched: 0,
+        }
+    }
+
+    fn advance(self, delta: usize) -> Self::Advance {
+        let bits = self.offset + delta;
+        BitAdvance {
+            fut: self.inner.advance(bits / 8).into_future(),
+            rest: Some((bits % 8, self.order)),
+        }
+    }
+}
+
+/// yield the bits of one byte at a time.
+///
+/// only the bytes needed by the size hint are copied from the underlying sequence.
+pub struct BitSegments<'a, S> {
+    inner: &'a mut S,
+    offset: usize,
+    order: BitOrder,
+    /// bytes copied from `inner`.
+    bytes: Vec<u8>,
+    /// index of the next byte in `bytes`.
+    pos: usize,
+    /// number of bytes of `inner` copied so far.
+    fetched: usize,
+}
+
+impl<'a, S: Sequence<Segment = [u8], Length = usize>> SegmentStream for BitSegments<'a, S> {
+    type Length = usize;
+    type Segment = [bool];
+    type Next<'b>
+        = BitSegmentsNext<'b>
+    where
+        Self: 'b;
+
+    fn next(&mut self, size_hint: usize) -> Self::Next<'_> {
+        if self.pos < self.bytes.len() {
+            let bits = self.take_byte();
+            return BitSegmentsNext::Ready(std::future::ready(Some(bits)));
+        }
+
+        let need = (self.offset + size_hint).div_ceil(8).max(1);
+        BitSegmentsNext::Fetch(Box::pin(async move {
+            // 読み込み済みのバイトを読み飛ばした位置から、必要な分だけ複製する。
+            let mut skip = self.fetched;
+            let mut segments = self.inner.segments();
+            let segment = loop {
+                let segment = segments.next(skip + need).await?;
+                if segment.len() > skip {
+                    break &segment[skip..];
+                }
+                skip -= segment.len();
+            };
+
+            let len = need.min(segment.len());
+            self.bytes.clear();
+            self.bytes.extend_from_slice(&segment[..len]);
+            drop(segments);
+
+            self.fetched += len;
+            self.pos = 0;
+            Some(self.take_byte())
+        }))
+    }
+}
+
+impl<'a, S> BitSegments<'a, S> {
+    fn take_byte(&mut self) -> &'static [bool] {
+        let byte = self.bytes[self.pos];
+        self.pos += 1;
+
+        // 読み済みのビットは先頭のバイトにのみ含まれる。
+        let offset = std::mem::take(&mut self.offset);
+        let bits = match self.order {
+            BitOrder::MsbFirst => &MSB_FIRST[byte as usize],
+            BitOrder::LsbFirst => &LSB_FIRST[byte as usize],
+        };
+        &bits[offset..]
+    }
+}
+
+pub enum BitSegmentsNext<'b> {
+    Ready(std::future::Ready<Option<&'b [bool]>>),
+    Fetch(Pin<Box<dyn 'b + Future<Output = Option<&'b [bool]>>>>),
+}
+
+impl<'b> Future for BitSegmentsNext<'b> {
+    type Output = Option<&'b [bool]>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.get_mut() {
+            BitSegmentsNext::Ready(fut) => Pin::new(fut).poll(cx),
+            BitSegmentsNext::Fetch(fut) => fut.as_mut().poll(cx),
+        }
+    }
+}
+
+static MSB_FIRST: [[bool; 8]; 256] = bit_table(BitOrder::MsbFirst);
+static LSB_FIRST: [[bool; 8]; 256] = bit_table(BitOrder::LsbFirst);
+
+/// bits of every byte value.
+const fn bit_table(order: BitOrder) -> [[bool; 8]; 256] {
+    let mut table = [[false; 8]; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut i = 0;
+        while i < 8 {
+            table[byte][i] = match order {
+                BitOrder::MsbFirst => byte & (0x80 >> i) != 0,
+                BitOrder::LsbFirst => byte & (0x01 << i) != 0,
+            };
+            i += 1;
+        }
+        byte += 1;
+    }
+    table
+}
+
+pub struct BitAdvance<S: Sequence> {
+    fut: <S::Advance as IntoFuture>::IntoFuture,
+    rest: Option<(usize, BitOrder)>,
+}
+
+impl<S: Sequence> Future for BitAdvance<S> {
+    type Output = BitSequence<S>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`はpinされたまま移動されない。`rest`はpinされない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = std::task::ready!(unsafe { Pin::new_unchecked(&mut this.fut) }.poll(cx));
+        let (offset, order) = this.rest.take().unwrap();
+
+        Poll::Ready(BitSequence {
+            inner,
+            offset,
+            order,
+        })
+    }
+}
+
+pub struct BitAnchor<A> {
+    inner: A,
+    offset: usize,
+}
+
+impl<S> RewindSequence for BitSequence<S>
+where
+    S: RewindSequence<Segment = [u8], Length = usize>,
+{
+    type Anchor = BitAnchor<S::Anchor>;
+    type Rewind = BitRewind<S>;
+
+    fn anchor(&self) -> Self::Anchor {
+        BitAnchor {
+            inner: self.inner.anchor(),
+            offset: self.offset,
+        }
+    }
+
+    fn rewind(self, anchor: Self::Anchor) -> Self::Rewind {
+        BitRewind {
+            fut: self.inner.rewind(anchor.inner),
+            rest: Some((anchor.offset, self.order)),
+        }
+    }
+}
+
+pub struct BitRewind<S: RewindSequence> {
+    fut: S::Rewind,
+    rest: Option<(usize, BitOrder)>,
+}
+
+impl<S: RewindSequence> Future for BitRewind<S> {
+    type Output = BitSequence<S>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`はpinされたまま移動されない。`rest`はpinされない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = std::task::ready!(unsafe { Pin::new_unchecked(&mut this.fut) }.poll(cx));
+        let (offset, order) = this.rest.take().unwrap();
+
+        Poll::Ready(BitSequence {
+            inner,
+            offset,
+            order,
+        })
+    }
+}
+
+impl<S> MeasuredSequence for BitSequence<S>
+where
+    S: MeasuredSequence<Segment = [u8], Length = usize>,
+    S::Metrics: Metrics<[bool]>,
+{
+    type Metrics = S::Metrics;
+
+    fn metrics(&self) -> Self::Metrics {
+        self.inner.metrics()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain;
+
+    async fn collect<S: Sequence<Segment = [bool], Length = usize>>(sequence: &mut S) -> Vec<bool> {
+        let mut buf = Vec::new();
+        let mut segments = sequence.segments();
+        while let Some(segment) = segments.next(0).await {
+            buf.extend_from_slice(segment);
+        }
+        buf
+    }
+
+    #[test]
+    fn read_bits_across_segments() {
+        pollster::block_on(async {
+            let input = chain([0b1000_0001u8].as_slice(), [0b0100_0000u8].as_slice());
+            let sequence = BitSequence::new(input, BitOrder::MsbFirst);
+            let anchor = sequence.anchor();
+
+            let mut sequence = sequence.advance(7).await;
+            assert_eq!(sequence.offset(), 7);
+            assert_eq!(
+                collect(&mut sequence).await,
+                [true, false, true, false, false, false, false, false, false]
+            );
+
+            let sequence = sequence.advance(2).await;
+            assert_eq!(sequence.offset(), 1);
+
+            let sequence = sequence.rewind(anchor).await;
+            assert_eq!(sequence.offset(), 0);
+            assert_eq!(sequence.into_inner().into_inner().1, [0b0100_0000]);
+
+            // 先頭のセグメントが空でも、読み済みのビットは次のバイトで読み飛ばす。
+            let input = chain([0xAAu8].as_slice(), [0b0001_0000u8].as_slice());
+            let mut sequence = BitSequence::new(input, BitOrder::MsbFirst)
+                .advance(11)
+                .await;
+            assert_eq!(
+                collect(&mut sequence).await,
+                [true, false, false, false, false]
+            );
+
+            let mut sequence = BitSequence::new([0b0000_0110u8].as_slice(), BitOrder::LsbFirst);
+            assert_eq!(
+                collect(&mut sequence).await,
+                [false, true, true, false, false, false, false, false]
+            );
+        })
+    }
+}
@@ -0,0 +1,552 @@
+use crate::{
+    measured::{IntoMeasured, Meter, Metrics},
+    MeasuredSequence, RecognizeSequence, RecordSegment, Recording, RewindSequence, SegmentStream,
+    Sequence, SequenceSegment,
+};
+use std::{
+    future::{Future, IntoFuture},
+    ops::{Add, Sub},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+pub fn chain<S0, S1>(first: S0, second: S1) -> Chain<S0, S1>
+where
+    S0: Sequence,
+    S1: Sequence<Segment = S0::Segment, Length = S0::Length>,
+{
+    Chain::new(first, second)
+}
+
+/// sequence which reads `first` to the end and then continues with `second`.
+///
+/// chain more than two sequences by nesting, e.g. `chain(chain(s0, s1), s2)`.
+/// `into_measured` measures the chain as one continuous sequence.
+#[derive(Debug)]
+pub struct Chain<S0, S1, T = ()> {
+    first: S0,
+    second: S1,
+    /// `true` if `first` has been advanced to the end and the chain is reading `second`.
+    exhausted: bool,
+    meter: T,
+}
+
+impl<S0, S1> Chain<S0, S1> {
+    pub fn new(first: S0, second: S1) -> Self {
+        Self {
+            first,
+            second,
+            exhausted: false,
+            meter: (),
+        }
+    }
+}
+
+impl<S0, S1, T> Chain<S0, S1, T> {
+    pub fn into_inner(self) -> (S0, S1) {
+        (self.first, self.second)
+    }
+}
+
+impl<S0, S1, T> Sequence for Chain<S0, S1, T>
+where
+    S0: Sequence,
+    S0::Segment: SequenceSegment<Length = S0::Length>,
+    S0::Length: Copy + Default + Ord + Add<Output = S0::Length> + Sub<Output = S0::Length>,
+    S1: Sequence<Segment = S0::Segment, Length = S0::Length>,
+    T: Meter<S0::Segment>,
+{
+    type Length = S0::Length;
+    type Segment = S0::Segment;
+    type Segments<'a>
+        = ChainSegments<'a, S0, S1>
+    where
+        Self: 'a;
+    type Advance = ChainAdvance<S0, S1, T>;
+
+    fn segments(&mut self) -> Self::Segments<'_> {
+        ChainSegments {
+            first: self.first.segments(),
+            second: self.second.segments(),
+            first_done: self.exhausted,
+        }
+    }
+
+    fn advance(self, delta: Self::Length) -> Self::Advance {
+        ChainAdvance {
+            state: AdvanceState::Peek {
+                chain: self,
+                remain: delta,
+            },
+        }
+    }
+}
+
+impl<S0, S1> IntoMeasured for Chain<S0, S1>
+where
+    S0: Sequence,
+    S0::Segment: SequenceSegment<Length = S0::Length>,
+    S0::Length: Copy + Default + Ord + Add<Output = S0::Length> + Sub<Output = S0::Length>,
+    S1: Sequence<Segment = S0::Segment, Length = S0::Length>,
+{
+    type Measured<M: Metrics<Self::Segment>> = Chain<S0, S1, M::Meter>;
+
+    fn into_measured_with<M: Metrics<Self::Segment>>(self, meter: M::Meter) -> Self::Measured<M> {
+        Chain {
+            first: self.first,
+            second: self.second,
+            exhausted: self.exhausted,
+            meter,
+        }
+    }
+}
+
+pub struct ChainSegments<'a, S0: 'a + Sequence, S1: 'a + Sequence> {
+    first: S0::Segments<'a>,
+    second: S1::Segments<'a>,
+    first_done: bool,
+}
+
+impl<'a, S0, S1> SegmentStream for ChainSegments<'a, S0, S1>
+where
+    S0: 'a + Sequence,
+    S0::Length: Clone,
+    S1: 'a + Sequence<Segment = S0::Segment, Length = S0::Length>,
+{
+    type Length = S0::Length;
+    type Segment = S0::Segment;
+    type Next<'b>
+        = ChainSegmentsNext<'b, S0::Segments<'a>, S1::Segments<'a>>
+    where
+        Self: 'b;
+
+    fn next(&mut self, size_hint: Self::Length) -> Self::Next<'_> {
+        if self.first_done {
+            return ChainSegmentsNext {
+                first: None,
+                second: Some(self.second.next(size_hint)),
+                pending: None,
+            };
+        }
+
+        ChainSegmentsNext {
+            first: Some(self.first.next(size_hint.clone())),
+            second: None,
+            pending: Some((&mut self.second, &mut self.first_done, size_hint)),
+        }
+    }
+}
+
+pub struct ChainSegmentsNext<'b, T0, T1>
+where
+    T0: 'b + SegmentStream,
+    T1: 'b + SegmentStream<Segment = T0::Segment, Length = T0::Length>,
+{
+    first: Option<T0::Next<'b>>,
+    second: Option<T1::Next<'b>>,
+    pending: Option<(&'b mut T1, &'b mut bool, T1::Length)>,
+}
+
+impl<'b, T0, T1> Future for ChainSegmentsNext<'b, T0, T1>
+where
+    T0: 'b + SegmentStream,
+    T1: 'b + SegmentStream<Segment = T0::Segment, Length = T0::Length>,
+{
+    type Output = Option<&'b T0::Segment>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `first`と`second`はpinされたまま移動されない。`pending`はpinされない。
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if let Some(fut) = this.first.as_mut() {
+            let fut = unsafe { Pin::new_unchecked(fut) };
+            match fut.poll(cx) {
+                Poll::Ready(Some(segment)) => return Poll::Ready(Some(segment)),
+                Poll::Ready(None) => {
+                    this.first = None;
+                    let (second, first_done, size_hint) = this.pending.take().unwrap();
+                    *first_done = true;
+                    this.second = Some(second.next(size_hint));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        let fut = this.second.as_mut().unwrap();
+        unsafe { Pin::new_unchecked(fut) }.poll(cx)
+    }
+}
+
+pub struct ChainAdvance<S0: Sequence, S1: Sequence, T> {
+    state: AdvanceState<S0, S1, T>,
+}
+
+enum AdvanceState<S0: Sequence, S1: Sequence, T> {
+    /// 先頭のセグメントを読み、一度に進める量を決める。
+    Peek {
+        chain: Chain<S0, S1, T>,
+        remain: S0::Length,
+    },
+    First {
+        fut: <S0::Advance as IntoFuture>::IntoFuture,
+        rest: Option<(S1, T)>,
+        remain: S0::Length,
+    },
+    Second {
+        fut: <S1::Advance as IntoFuture>::IntoFuture,
+        rest: Option<(S0, T)>,
+        remain: S0::Length,
+    },
+    Done,
+}
+
+impl<S0, S1, T> Future for ChainAdvance<S0, S1, T>
+where
+    S0: Sequence,
+    S0::Segment: SequenceSegment<Length = S0::Length>,
+    S0::Length: Copy + Default + Ord + Add<Output = S0::Length> + Sub<Output = S0::Length>,
+    S1: Sequence<Segment = S0::Segment, Length = S0::Length>,
+    T: Meter<S0::Segment>,
+{
+    type Output = Chain<S0, S1, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `First`・`Second`の`fut`は完了するまで移動されない。`Peek`は何もpinしない。
+        let this = unsafe { self.get_unchecked_mut() };
+
+        loop {
+            match &mut this.state {
+                AdvanceState::Peek { .. } => {
+                    let AdvanceState::Peek { chain, remain } =
+                        std::mem::replace(&mut this.state, AdvanceState::Done)
+                    else {
+                        unreachable!()
+                    };
+                    if remain == S0::Length::default() {
+                        return Poll::Ready(chain);
+                    }
+
+                    // 進めた量は`remain`に残るため、Pendingの場合も読み直すのは先頭のセグメントだけである。
+                    let Chain {
+                        mut first,
+                        mut second,
+                        exhausted,
+                        meter,
+                    } = chain;
+                    let (meter, step) = if exhausted {
+                        poll_step(&mut second, meter, remain, cx)
+                    } else {
+                        poll_step(&mut first, meter, remain, cx)
+                    };
+
+                    let Poll::Ready(step) = step else {
+                        this.state = AdvanceState::Peek {
+                            chain: Chain {
+                                first,
+                                second,
+                                exhausted,
+                                meter,
+                            },
+                            remain,
+                        };
+                        return Poll::Pending;
+                    };
+
+                    this.state = match (step, exhausted) {
+                        (Some(step), false) => AdvanceState::First {
+                            fut: first.advance(step).into_future(),
+                            rest: Some((second, meter)),
+                            remain: remain - step,
+                        },
+                        (Some(step), true) => AdvanceState::Second {
+                            fut: second.advance(step).into_future(),
+                            rest: Some((first, meter)),
+                            remain: remain - step,
+                        },
+                        (None, false) => AdvanceState::Peek {
+                            chain: Chain {
+                                first,
+                                second,
+                                exhausted: true,
+                                meter,
+                            },
+                            remain,
+                        },
+                        (None, true) => {
+                            return Poll::Ready(Chain {
+                                first,
+                                second,
+                                exhausted,
+                                meter,
+                            });
+                        }
+                    };
+                }
+                AdvanceState::First { fut, rest, remain } => {
+                    let fut = unsafe { Pin::new_unchecked(fut) };
+                    let first = std::task::ready!(fut.poll(cx));
+                    let (second, meter) = rest.take().unwrap();
+                    let remain = *remain;
+                    this.state = AdvanceState::Peek {
+                        chain: Chain {
+                            first,
+                            second,
+                            exhausted: false,
+                            meter,
+                        },
+                        remain,
+                    };
+                }
+                AdvanceState::Second { fut, rest, remain } => {
+                    let fut = unsafe { Pin::new_unchecked(fut) };
+                    let second = std::task::ready!(fut.poll(cx));
+                    let (first, meter) = rest.take().unwrap();
+                    let remain = *remain;
+                    this.state = AdvanceState::Peek {
+                        chain: Chain {
+                            first,
+                            second,
+                            exhausted: true,
+                            meter,
+                        },
+                        remain,
+                    };
+                }
+                AdvanceState::Done => panic!("`ChainAdvance` was polled after completion."),
+            }
+        }
+    }
+}
+
+/// measure the part of the first non-empty segment of `sequence` within `remain`.
+///
+/// return `None` if `sequence` has no segments left.
+fn poll_step<S, T>(
+    sequence: &mut S,
+    meter: T,
+    remain: S::Length,
+    cx: &mut Context<'_>,
+) -> (T, Poll<Option<S::Length>>)
+where
+    S: Sequence,
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord,
+    T: Meter<S::Segment>,
+{
+    let mut segments = sequence.segments();
+
+    loop {
+        let next = std::pin::pin!(segments.next(remain));
+        match next.poll(cx) {
+            Poll::Ready(Some(segment)) if segment.len() == S::Length::default() => continue,
+            Poll::Ready(Some(segment)) => {
+                let step = segment.len().min(remain);
+                let (consumed, _) = segment.split_at(step);
+                return (meter.advance(consumed), Poll::Ready(Some(step)));
+            }
+            Poll::Ready(None) => return (meter, Poll::Ready(None)),
+            Poll::Pending => return (meter, Poll::Pending),
+        }
+    }
+}
+
+pub struct ChainAnchor<A0, A1, T> {
+    first: A0,
+    second: A1,
+    exhausted: bool,
+    meter: T,
+}
+
+impl<S0, S1, T> RewindSequence for Chain<S0, S1, T>
+where
+    Self: Sequence,
+    S0: RewindSequence,
+    S1: RewindSequence,
+    T: Clone,
+{
+    type Anchor = ChainAnchor<S0::Anchor, S1::Anchor, T>;
+    type Rewind = ChainRewind<S0, S1, T>;
+
+    fn anchor(&self) -> Self::Anchor {
+        ChainAnchor {
+            first: self.first.anchor(),
+            second: self.second.anchor(),
+            exhausted: self.exhausted,
+            meter: self.meter.clone(),
+        }
+    }
+
+    fn rewind(self, anchor: Self::Anchor) -> Self::Rewind {
+        ChainRewind {
+            state: RewindState::First {
+                fut: self.first.rewind(anchor.first),
+                second: Some((self.second, anchor.second)),
+            },
+            rest: Some((anchor.exhausted, anchor.meter)),
+        }
+    }
+}
+
+pub struct ChainRewind<S0: RewindSequence, S1: RewindSequence, T> {
+    state: RewindState<S0, S1>,
+    rest: Option<(bool, T)>,
+}
+
+enum RewindState<S0: RewindSequence, S1: RewindSequence> {
+    First {
+        fut: S0::Rewind,
+        second: Option<(S1, S1::Anchor)>,
+    },
+    Second {
+        first: Option<S0>,
+        fut: S1::Rewind,
+    },
+    Done,
+}
+
+impl<S0: RewindSequence, S1: RewindSequence, T> Future for ChainRewind<S0, S1, T> {
+    type Output = Chain<S0, S1, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`は完了するまで移動されない。
+        let this = unsafe { self.get_unchecked_mut() };
+
+        loop {
+            match &mut this.state {
+                RewindState::First { fut, second } => {
+                    let first = std::task::ready!(unsafe { Pin::new_unchecked(fut) }.poll(cx));
+                    let (second, anchor) = second.take().unwrap();
+                    this.state = RewindState::Second {
+                        first: Some(first),
+                        fut: second.rewind(anchor),
+                    };
+                }
+                RewindState::Second { first, fut } => {
+                    let second = std::task::ready!(unsafe { Pin::new_unchecked(fut) }.poll(cx));
+                    let first = first.take().unwrap();
+                    let (exhausted, meter) = this.rest.take().unwrap();
+                    this.state = RewindState::Done;
+                    return Poll::Ready(Chain {
+                        first,
+                        second,
+                        exhausted,
+                        meter,
+                    });
+                }
+                RewindState::Done => panic!("`ChainRewind` was polled after completion."),
+            }
+        }
+    }
+}
+
+impl<S0, S1, T> MeasuredSequence for Chain<S0, S1, T>
+where
+    Self: Sequence<Segment = S0::Segment>,
+    S0: Sequence,
+    T: Meter<S0::Segment>,
+{
+    type Metrics = T::Metrics;
+
+    fn metrics(&self) -> Self::Metrics {
+        self.meter.metrics()
+    }
+}
+
+impl<S0, S1, T> RecognizeSequence for Chain<S0, S1, T>
+where
+    Self: Sequence<Segment = S0::Segment, Length = S0::Length>,
+    S0: Sequence,
+    S0::Segment: RecordSegment<Length = S0::Length>,
+    S0::Length: Copy + Default + Ord + Sub<Output = S0::Length>,
+{
+    // 継ぎ目をまたぐ部分は借用できないため複製する。
+    type Recorder = Recording<Self>;
+    type Mark = ();
+    type Span = <S0::Segment as RecordSegment>::Owned;
+
+    fn record(self) -> (Self::Recorder, Self::Mark) {
+        (Recording::new(self), ())
+    }
+
+    fn recognize(recorder: Self::Recorder, _: Self::Mark) -> (Self::Span, Self) {
+        recorder.into_parts()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::BytesDelta;
+
+    async fn collect<S: Sequence<Segment = str, Length = BytesDelta>>(sequence: &mut S) -> String {
+        let mut buf = String::new();
+        let mut segments = sequence.segments();
+        while let Some(segment) = segments.next(BytesDelta::ZERO).await {
+            buf.push_str(segment);
+        }
+        buf
+    }
+
+    #[test]
+    fn segments_flow_into_second() {
+        pollster::block_on(async {
+            let mut sequence = chain(chain("ab", ""), "cd");
+            assert_eq!(collect(&mut sequence).await, "abcd");
+        })
+    }
+
+    #[test]
+    fn advance_across_seam() {
+        pollster::block_on(async {
+            let text = "0123456789";
+            for i in 0..=text.len() {
+                for j in i..=text.len() {
+                    let (l, r) = text.split_at(i);
+                    let sequence = chain(l, r).advance(BytesDelta::from_bytes(j)).await;
+                    let mut sequence = sequence.advance(BytesDelta::ZERO).await;
+                    assert_eq!(collect(&mut sequence).await, &text[j..]);
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn rewind_and_measure_across_seam() {
+        pollster::block_on(async {
+            let sequence = chain(b"abc".as_slice(), b"def".as_slice());
+            let sequence = sequence.into_measured::<usize>().advance(2).await;
+            let anchor = sequence.anchor();
+            assert_eq!(sequence.metrics(), 2);
+
+            let mut sequence = sequence.advance(3).await;
+            assert_eq!(sequence.metrics(), 5);
+            assert_eq!(collect_items(&mut sequence).await, b"f");
+
+            let mut sequence = sequence.rewind(anchor).await;
+            assert_eq!(sequence.metrics(), 2);
+            assert_eq!(collect_items(&mut sequence).await, b"cdef");
+        })
+    }
+
+    #[test]
+    fn recognize_across_seam() {
+        pollster::block_on(async {
+            let (recorder, mark) = chain("ab", "cd").record();
+            let recorder = recorder.advance(BytesDelta::from_bytes(3)).await;
+            let (span, mut rest) = Chain::recognize(recorder, mark);
+            assert_eq!(span, "abc");
+            assert_eq!(collect(&mut rest).await, "d");
+        })
+    }
+
+    async fn collect_items<S: Sequence<Segment = [u8], Length = usize>>(
+        sequence: &mut S,
+    ) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let mut segments = sequence.segments();
+        while let Some(segment) = segments.next(0).await {
+            buf.extend_from_slice(segment);
+        }
+        buf
+    }
+}
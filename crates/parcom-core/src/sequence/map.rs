@@ -0,0 +1,716 @@
+use crate::{
+    measured::Metrics, primitive::BytesDelta, MeasuredSequence, RewindSequence, SegmentStream,
+    Sequence, SequenceSegment,
+};
+use std::{
+    borrow::Borrow,
+    future::{Future, IntoFuture},
+    marker::PhantomData,
+    ops::{Add, Sub},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+pub fn filter_map_chars<S, F>(sequence: S, f: F) -> Mapped<S, FilterMapChars<F>>
+where
+    S: Sequence<Segment = str, Length = BytesDelta>,
+    F: Fn(char) -> Option<char>,
+{
+    Mapped::new(sequence, FilterMapChars::new(f))
+}
+
+pub fn map_chars<S, F>(
+    sequence: S,
+    f: F,
+) -> Mapped<S, FilterMapChars<impl Fn(char) -> Option<char>>>
+where
+    S: Sequence<Segment = str, Length = BytesDelta>,
+    F: Fn(char) -> char,
+{
+    filter_map_chars(sequence, move |c| Some(f(c)))
+}
+
+pub fn filter_chars<S, F>(
+    sequence: S,
+    predicate: F,
+) -> Mapped<S, FilterMapChars<impl Fn(char) -> Option<char>>>
+where
+    S: Sequence<Segment = str, Length = BytesDelta>,
+    F: Fn(char) -> bool,
+{
+    filter_map_chars(sequence, move |c| predicate(c).then_some(c))
+}
+
+pub fn filter_map_items<S, T, U, F>(sequence: S, f: F) -> Mapped<S, FilterMapItems<T, F>>
+where
+    S: Sequence<Segment = [T], Length = usize>,
+    F: Fn(&T) -> Option<U>,
+{
+    Mapped::new(sequence, FilterMapItems::new(f))
+}
+
+pub fn map_items<S, T, U, F>(
+    sequence: S,
+    f: F,
+) -> Mapped<S, FilterMapItems<T, impl Fn(&T) -> Option<U>>>
+where
+    S: Sequence<Segment = [T], Length = usize>,
+    F: Fn(&T) -> U,
+{
+    filter_map_items(sequence, move |item| Some(f(item)))
+}
+
+pub fn filter_items<S, T, F>(
+    sequence: S,
+    predicate: F,
+) -> Mapped<S, FilterMapItems<T, impl Fn(&T) -> Option<T>>>
+where
+    S: Sequence<Segment = [T], Length = usize>,
+    T: Clone,
+    F: Fn(&T) -> bool,
+{
+    filter_map_items(sequence, move |item| predicate(item).then(|| item.clone()))
+}
+
+pub fn decode_utf16<S>(sequence: S) -> Mapped<S, DecodeUtf16>
+where
+    S: Sequence<Segment = [u16], Length = usize>,
+{
+    Mapped::new(sequence, DecodeUtf16)
+}
+
+/// mapping from segments of an underlying sequence to segments of a `Mapped` sequence.
+pub trait SegmentMap<T: ?Sized + SequenceSegment> {
+    type Segment: ?Sized + SequenceSegment;
+    type Buffer: Default + Borrow<Self::Segment>;
+    /// state carried from one segment to the next, e.g. a pending high surrogate.
+    type State: Clone;
+
+    fn initial_state(&self) -> Self::State;
+
+    /// overwrite `buf` with the mapped `segment`.
+    fn map(&self, state: &mut Self::State, segment: &T, buf: &mut Self::Buffer);
+
+    /// overwrite `buf` with the output left in `state` at the end of the underlying sequence.
+    fn flush(&self, state: &mut Self::State, buf: &mut Self::Buffer) {
+        let _ = state;
+        *buf = Default::default();
+    }
+
+    /// map the head of `segment` until `delta` is produced or `segment` runs out.
+    /// return the consumed length of `segment` and the produced length.
+    fn measure(
+        &self,
+        state: &mut Self::State,
+        segment: &T,
+        delta: <Self::Segment as SequenceSegment>::Length,
+    ) -> (T::Length, <Self::Segment as SequenceSegment>::Length);
+}
+
+/// sequence whose segments are the segments of `inner` mapped by `M`.
+///
+/// lengths are counted in mapped segments, while metrics are taken from `inner`.
+pub struct Mapped<S: Sequence, M: SegmentMap<S::Segment>>
+where
+    S::Segment: SequenceSegment,
+{
+    inner: S,
+    mapping: M,
+    state: M::State,
+    /// buffer reused by `segments`.
+    buf: M::Buffer,
+}
+
+impl<S: Sequence, M: SegmentMap<S::Segment>> Mapped<S, M>
+where
+    S::Segment: SequenceSegment,
+{
+    pub fn new(inner: S, mapping: M) -> Self {
+        let state = mapping.initial_state();
+        Self {
+            inner,
+            mapping,
+            state,
+            buf: Default::default(),
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, M> Sequence for Mapped<S, M>
+where
+    S: Sequence,
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord + Add<Output = S::Length>,
+    M: SegmentMap<S::Segment>,
+    <M::Segment as SequenceSegment>::Length:
+        Copy + Sub<Output = <M::Segment as SequenceSegment>::Length>,
+{
+    type Length = <M::Segment as SequenceSegment>::Length;
+    type Segment = M::Segment;
+    type Segments<'a>
+        = MappedSegments<'a, S, M>
+    where
+        Self: 'a;
+    type Advance = MappedAdvance<S, M>;
+
+    fn segments(&mut self) -> Self::Segments<'_> {
+        MappedSegments {
+            inner: self.inner.segments(),
+            mapping: &self.mapping,
+            state: self.state.clone(),
+            buf: &mut self.buf,
+        }
+    }
+
+    fn advance(self, delta: Self::Length) -> Self::Advance {
+        MappedAdvance {
+            state: AdvanceState::Measure {
+                sequence: self,
+                delta,
+            },
+        }
+    }
+}
+
+pub struct MappedSegments<'a, S: 'a + Sequence, M: SegmentMap<S::Segment>>
+where
+    S::Segment: SequenceSegment,
+{
+    inner: S::Segments<'a>,
+    mapping: &'a M,
+    state: M::State,
+    buf: &'a mut M::Buffer,
+}
+
+impl<'a, S, M> SegmentStream for MappedSegments<'a, S, M>
+where
+    S: 'a + Sequence,
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Default,
+    M: SegmentMap<S::Segment>,
+{
+    type Length = <M::Segment as SequenceSegment>::Length;
+    type Segment = M::Segment;
+    type Next<'b>
+        = MappedSegmentsNext<'b, S::Segments<'a>, M>
+    where
+        Self: 'b;
+
+    fn next(&mut self, _: Self::Length) -> Self::Next<'_> {
+        // 写像後の長さから写像前の長さは求まらないため、size_hintは渡さない。
+        MappedSegmentsNext {
+            fut: self.inner.next(Default::default()),
+            mapping: self.mapping,
+            state: &mut self.state,
+            buf: Some(&mut *self.buf),
+        }
+    }
+}
+
+pub struct MappedSegmentsNext<'b, T, M>
+where
+    T: 'b + SegmentStream,
+    T::Segment: SequenceSegment,
+    M: SegmentMap<T::Segment>,
+{
+    fut: T::Next<'b>,
+    mapping: &'b M,
+    state: &'b mut M::State,
+    buf: Option<&'b mut M::Buffer>,
+}
+
+impl<'b, T, M> Future for MappedSegmentsNext<'b, T, M>
+where
+    T: 'b + SegmentStream,
+    T::Segment: SequenceSegment,
+    M: SegmentMap<T::Segment>,
+{
+    type Output = Option<&'b M::Segment>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`はpinされたまま移動されない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let fut = unsafe { Pin::new_unchecked(&mut this.fut) };
+
+        let segment = std::task::ready!(fut.poll(cx));
+        let buf = this.buf.take().unwrap();
+
+        let Some(segment) = segment else {
+            // 末尾で状態に残った出力を返す。出力がなくなれば終端である。
+            this.mapping.flush(this.state, buf);
+            let buf: &'b M::Buffer = buf;
+            let buf = buf.borrow();
+            return Poll::Ready((buf.len() > Default::default()).then_some(buf));
+        };
+
+        this.mapping.map(this.state, segment, buf);
+        let buf: &'b M::Buffer = buf;
+        Poll::Ready(Some(buf.borrow()))
+    }
+}
+
+pub struct MappedAdvance<S: Sequence, M: SegmentMap<S::Segment>>
+where
+    S::Segment: SequenceSegment,
+{
+    state: AdvanceState<S, M>,
+}
+
+enum AdvanceState<S: Sequence, M: SegmentMap<S::Segment>>
+where
+    S::Segment: SequenceSegment,
+{
+    Measure {
+        sequence: Mapped<S, M>,
+        delta: <M::Segment as SequenceSegment>::Length,
+    },
+    Advance {
+        fut: <S::Advance as IntoFuture>::IntoFuture,
+        mapping: Option<(M, M::Buffer)>,
+        state: Option<M::State>,
+    },
+    Done,
+}
+
+impl<S, M> Future for MappedAdvance<S, M>
+where
+    S: Sequence,
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord + Add<Output = S::Length>,
+    M: SegmentMap<S::Segment>,
+    <M::Segment as SequenceSegment>::Length:
+        Copy + Sub<Output = <M::Segment as SequenceSegment>::Length>,
+{
+    type Output = Mapped<S, M>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `Advance`の`fut`は完了するまで移動されない。`Measure`は何もpinしない。
+        let this = unsafe { self.get_unchecked_mut() };
+
+        loop {
+            match &mut this.state {
+                AdvanceState::Measure { sequence, delta } => {
+                    let delta = *delta;
+                    let (consumed, state) = std::task::ready!(poll_measure(sequence, delta, cx));
+                    let AdvanceState::Measure { sequence, .. } =
+                        std::mem::replace(&mut this.state, AdvanceState::Done)
+                    else {
+                        unreachable!()
+                    };
+
+                    this.state = AdvanceState::Advance {
+                        fut: sequence.inner.advance(consumed).into_future(),
+                        mapping: Some((sequence.mapping, sequence.buf)),
+                        state: Some(state),
+                    };
+                }
+                AdvanceState::Advance {
+                    fut,
+                    mapping,
+                    state,
+                } => {
+                    let inner = std::task::ready!(unsafe { Pin::new_unchecked(fut) }.poll(cx));
+                    let (mapping, buf) = mapping.take().unwrap();
+                    let sequence = Mapped {
+                        inner,
+                        mapping,
+                        state: state.take().unwrap(),
+                        buf,
+                    };
+                    this.state = AdvanceState::Done;
+                    return Poll::Ready(sequence);
+                }
+                AdvanceState::Done => panic!("`MappedAdvance` was polled after completion."),
+            }
+        }
+    }
+}
+
+/// return the length of `inner` to consume to produce `delta`, and the state after consuming it.
+fn poll_measure<S, M>(
+    sequence: &mut Mapped<S, M>,
+    delta: <M::Segment as SequenceSegment>::Length,
+    cx: &mut Context<'_>,
+) -> Poll<(S::Length, M::State)>
+where
+    S: Sequence,
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord + Add<Output = S::Length>,
+    M: SegmentMap<S::Segment>,
+    <M::Segment as SequenceSegment>::Length:
+        Copy + Sub<Output = <M::Segment as SequenceSegment>::Length>,
+{
+    // セグメントのストリームはpollをまたいで保持できないため、Pendingの場合は次回のpollで先頭から数え直す。
+    let mut state = sequence.state.clone();
+    let mut consumed = S::Length::default();
+    let mut remain = delta;
+    let mut segments = sequence.inner.segments();
+
+    while remain > Default::default() {
+        let next = std::pin::pin!(segments.next(Default::default()));
+        match next.poll(cx) {
+            Poll::Ready(Some(segment)) => {
+                let (c, p) = sequence.mapping.measure(&mut state, segment, remain);
+                consumed = consumed + c;
+                if p >= remain {
+                    break;
+                }
+                remain = remain - p;
+            }
+            Poll::Ready(None) => {
+                // 末尾で状態に残った出力も数える。一部だけを進めることはできないため、収まる場合のみ消費する。
+                let mut flushed = state.clone();
+                sequence.mapping.flush(&mut flushed, &mut sequence.buf);
+                if sequence.buf.borrow().len() <= remain {
+                    state = flushed;
+                }
+                break;
+            }
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+
+    Poll::Ready((consumed, state))
+}
+
+pub struct MappedAnchor<A, St> {
+    inner: A,
+    state: St,
+}
+
+impl<S, M> RewindSequence for Mapped<S, M>
+where
+    Self: Sequence,
+    S: RewindSequence,
+    S::Segment: SequenceSegment,
+    M: SegmentMap<S::Segment>,
+{
+    type Anchor = MappedAnchor<S::Anchor, M::State>;
+    type Rewind = MappedRewind<S, M>;
+
+    fn anchor(&self) -> Self::Anchor {
+        MappedAnchor {
+            inner: self.inner.anchor(),
+            state: self.state.clone(),
+        }
+    }
+
+    fn rewind(self, anchor: Self::Anchor) -> Self::Rewind {
+        MappedRewind {
+            fut: self.inner.rewind(anchor.inner),
+            rest: Some((self.mapping, anchor.state, self.buf)),
+        }
+    }
+}
+
+pub struct MappedRewind<S: RewindSequence, M: SegmentMap<S::Segment>>
+where
+    S::Segment: SequenceSegment,
+{
+    fut: S::Rewind,
+    rest: Option<(M, M::State, M::Buffer)>,
+}
+
+impl<S: RewindSequence, M: SegmentMap<S::Segment>> Future for MappedRewind<S, M>
+where
+    S::Segment: SequenceSegment,
+{
+    type Output = Mapped<S, M>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`はpinされたまま移動されない。`rest`はpinされない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = std::task::ready!(unsafe { Pin::new_unchecked(&mut this.fut) }.poll(cx));
+        let (mapping, state, buf) = this.rest.take().unwrap();
+
+        Poll::Ready(Mapped {
+            inner,
+            mapping,
+            state,
+            buf,
+        })
+    }
+}
+
+impl<S, M> MeasuredSequence for Mapped<S, M>
+where
+    Self: Sequence<Segment = M::Segment>,
+    S: MeasuredSequence,
+    S::Segment: SequenceSegment,
+    S::Metrics: Metrics<M::Segment>,
+    M: SegmentMap<S::Segment>,
+{
+    type Metrics = S::Metrics;
+
+    fn metrics(&self) -> Self::Metrics {
+        self.inner.metrics()
+    }
+}
+
+pub struct FilterMapChars<F: Fn(char) -> Option<char>> {
+    f: F,
+}
+
+impl<F: Fn(char) -> Option<char>> FilterMapChars<F> {
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<F: Fn(char) -> Option<char>> SegmentMap<str> for FilterMapChars<F> {
+    type Segment = str;
+    type Buffer = String;
+    type State = ();
+
+    fn initial_state(&self) -> Self::State {}
+
+    fn map(&self, _: &mut Self::State, segment: &str, buf: &mut Self::Buffer) {
+        buf.clear();
+        buf.extend(segment.chars().filter_map(&self.f));
+    }
+
+    fn measure(
+        &self,
+        _: &mut Self::State,
+        segment: &str,
+        delta: BytesDelta,
+    ) -> (BytesDelta, BytesDelta) {
+        let mut produced = BytesDelta::ZERO;
+        for (i, c) in segment.char_indices() {
+            let Some(mapped) = (self.f)(c) else {
+                continue;
+            };
+
+            produced = produced + BytesDelta::from_char(mapped);
+            if produced >= delta {
+                return (BytesDelta::from_bytes(i + c.len_utf8()), produced);
+            }
+        }
+
+        (BytesDelta::from_str(segment), produced)
+    }
+}
+
+pub struct FilterMapItems<T, F> {
+    f: F,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<T, F> FilterMapItems<T, F> {
+    pub fn new<U>(f: F) -> Self
+    where
+        F: Fn(&T) -> Option<U>,
+    {
+        Self {
+            f,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, U, F: Fn(&T) -> Option<U>> SegmentMap<[T]> for FilterMapItems<T, F> {
+    type Segment = [U];
+    type Buffer = Vec<U>;
+    type State = ();
+
+    fn initial_state(&self) -> Self::State {}
+
+    fn map(&self, _: &mut Self::State, segment: &[T], buf: &mut Self::Buffer) {
+        buf.clear();
+        buf.extend(segment.iter().filter_map(&self.f));
+    }
+
+    fn measure(&self, _: &mut Self::State, segment: &[T], delta: usize) -> (usize, usize) {
+        let mut produced = 0;
+        for (i, item) in segment.iter().enumerate() {
+            if (self.f)(item).is_none() {
+                continue;
+            }
+
+            produced += 1;
+            if produced >= delta {
+                return (i + 1, produced);
+            }
+        }
+
+        (segment.len(), produced)
+    }
+}
+
+/// decode UTF-16 code units into chars. unpaired surrogates are decoded as `U+FFFD`.
+pub struct DecodeUtf16;
+
+impl SegmentMap<[u16]> for DecodeUtf16 {
+    type Segment = [char];
+    type Buffer = Vec<char>;
+    /// a high surrogate waiting for the following low surrogate.
+    type State = Option<u16>;
+
+    fn initial_state(&self) -> Self::State {
+        None
+    }
+
+    fn map(&self, state: &mut Self::State, segment: &[u16], buf: &mut Self::Buffer) {
+        buf.clear();
+        for &unit in segment {
+            if let Some(high) = state.take() {
+                if is_low_surrogate(unit) {
+                    buf.push(combine_surrogates(high, unit));
+                    continue;
+                }
+                buf.push(char::REPLACEMENT_CHARACTER);
+            }
+
+            if is_high_surrogate(unit) {
+                *state = Some(unit);
+            } else {
+                buf.push(char::from_u32(unit as u32).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+        }
+    }
+
+    fn measure(&self, state: &mut Self::State, segment: &[u16], delta: usize) -> (usize, usize) {
+        let mut produced = 0;
+        for (i, &unit) in segment.iter().enumerate() {
+            if state.take().is_some() {
+                produced += 1;
+                if is_low_surrogate(unit) {
+                    if produced >= delta {
+                        return (i + 1, produced);
+                    }
+                    continue;
+                }
+
+                // 対になっていない上位サロゲートだけで`delta`に達した場合、`unit`は消費しない。
+                if produced >= delta {
+                    return (i, produced);
+                }
+            }
+
+            if is_high_surrogate(unit) {
+                *state = Some(unit);
+                continue;
+            }
+
+            produced += 1;
+            if produced >= delta {
+                return (i + 1, produced);
+            }
+        }
+
+        (segment.len(), produced)
+    }
+
+    fn flush(&self, state: &mut Self::State, buf: &mut Self::Buffer) {
+        buf.clear();
+        if state.take().is_some() {
+            buf.push(char::REPLACEMENT_CHARACTER);
+        }
+    }
+}
+
+fn is_high_surrogate(unit: u16) -> bool {
+    (0xD800..0xDC00).contains(&unit)
+}
+
+fn is_low_surrogate(unit: u16) -> bool {
+    (0xDC00..0xE000).contains(&unit)
+}
+
+fn combine_surrogates(high: u16, low: u16) -> char {
+    let c = 0x10000 + (((high as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00);
+    char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::measured::IntoMeasured;
+
+    async fn collect<S: Sequence<Segment = str, Length = BytesDelta>>(sequence: &mut S) -> String {
+        let mut buf = String::new();
+        let mut segments = sequence.segments();
+        while let Some(segment) = segments.next(BytesDelta::ZERO).await {
+            buf.push_str(segment);
+        }
+        buf
+    }
+
+    async fn collect_chars<S: Sequence<Segment = [char], Length = usize>>(
+        sequence: &mut S,
+    ) -> Vec<char> {
+        let mut buf = Vec::new();
+        let mut segments = sequence.segments();
+        while let Some(segment) = segments.next(0).await {
+            buf.extend_from_slice(segment);
+        }
+        buf
+    }
+
+    #[test]
+    fn lowercase_and_skip_spaces() {
+        pollster::block_on(async {
+            let sequence = map_chars(" SELECT  a", |c| c.to_ascii_lowercase());
+            let mut sequence = filter_chars(sequence, |c| c != ' ');
+            assert_eq!(collect(&mut sequence).await, "selecta");
+
+            let mut sequence = sequence.advance(BytesDelta::from_bytes(6)).await;
+            assert_eq!(collect(&mut sequence).await, "a");
+            assert_eq!(sequence.into_inner().into_inner(), "  a");
+        })
+    }
+
+    #[test]
+    fn metrics_point_at_underlying_location() {
+        pollster::block_on(async {
+            let input = b"a, b, c".as_slice().into_measured::<usize>();
+            let sequence = filter_items(input, |b| *b != b' ' && *b != b',');
+            let anchor = sequence.anchor();
+
+            let sequence = sequence.advance(2).await;
+            assert_eq!(sequence.metrics(), 4);
+
+            let sequence = sequence.rewind(anchor).await;
+            assert_eq!(sequence.metrics(), 0);
+        })
+    }
+
+    #[test]
+    fn decode_surrogate_pairs_across_segments() {
+        pollster::block_on(async {
+            let text = "a😀b\u{FFFD}";
+            let mut units: Vec<u16> = text.encode_utf16().collect();
+            units.insert(3, 0xD800);
+
+            let sequence = decode_utf16(units.as_slice());
+            let sequence = sequence.advance(1).await;
+            assert_eq!(sequence.state, None);
+
+            let mut sequence = sequence.advance(2).await;
+            let mut segments = sequence.segments();
+            let rest = segments.next(0).await.unwrap().to_vec();
+            assert_eq!(rest, ['b', '\u{FFFD}']);
+        })
+    }
+
+    #[test]
+    fn flush_trailing_high_surrogate() {
+        pollster::block_on(async {
+            let units = [0x61, 0xD83D];
+
+            let mut sequence = decode_utf16(units.as_slice());
+            let mut segments = sequence.segments();
+            assert_eq!(segments.next(0).await, Some(['a'].as_slice()));
+            assert_eq!(segments.next(0).await, Some(['\u{FFFD}'].as_slice()));
+            assert_eq!(segments.next(0).await, None);
+
+            let mut sequence = sequence.advance(2).await;
+            assert_eq!(sequence.state, None);
+            assert_eq!(collect_chars(&mut sequence).await, []);
+        })
+    }
+}
@@ -50,6 +50,19 @@ impl<S: ?Sized + Measureable<usize>> Metrics<S> for usize {
     type Meter = usize;
 }
 
+/// meter which measures nothing.
+impl<S: ?Sized> Meter<S> for () {
+    type Metrics = ();
+
+    fn advance(self, _: &S) -> Self {}
+
+    fn metrics(&self) -> Self::Metrics {}
+}
+
+impl<S: ?Sized> Metrics<S> for () {
+    type Meter = ();
+}
+
 impl<T> Measureable<usize> for [T] {
     fn measure(&self) -> usize {
         self.len()
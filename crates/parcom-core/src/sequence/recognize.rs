@@ -0,0 +1,324 @@
+use crate::{
+    primitive::BytesDelta, MeasuredSequence, RewindSequence, SegmentStream, Sequence,
+    SequenceSegment,
+};
+use std::{
+    future::{Future, IntoFuture},
+    ops::Sub,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// sequence from which the part consumed by a parser can be taken out.
+pub trait RecognizeSequence: Sequence {
+    /// sequence on which the parser runs.
+    type Recorder: Sequence<Segment = Self::Segment, Length = Self::Length>;
+    type Mark;
+    /// the consumed part.
+    type Span;
+
+    fn record(self) -> (Self::Recorder, Self::Mark);
+    /// return the part consumed between `mark` and `recorder`, and `recorder` as `Self`.
+    fn recognize(recorder: Self::Recorder, mark: Self::Mark) -> (Self::Span, Self);
+}
+
+/// segment which can be copied into an owned buffer.
+pub trait RecordSegment: SequenceSegment {
+    type Owned: Default;
+
+    fn record(&self, owned: &mut Self::Owned);
+    fn recorded_len(owned: &Self::Owned) -> Self::Length;
+    fn truncate(owned: &mut Self::Owned, len: Self::Length);
+}
+
+impl RecordSegment for str {
+    type Owned = String;
+
+    fn record(&self, owned: &mut Self::Owned) {
+        owned.push_str(self);
+    }
+
+    fn recorded_len(owned: &Self::Owned) -> Self::Length {
+        BytesDelta::from_str(owned)
+    }
+
+    fn truncate(owned: &mut Self::Owned, len: Self::Length) {
+        owned.truncate(len.to_bytes());
+    }
+}
+
+impl<T: Clone> RecordSegment for [T] {
+    type Owned = Vec<T>;
+
+    fn record(&self, owned: &mut Self::Owned) {
+        owned.extend_from_slice(self);
+    }
+
+    fn recorded_len(owned: &Self::Owned) -> Self::Length {
+        owned.len()
+    }
+
+    fn truncate(owned: &mut Self::Owned, len: Self::Length) {
+        owned.truncate(len);
+    }
+}
+
+/// sequence which copies the segments it advances over.
+///
+/// used as `RecognizeSequence::Recorder` of sequences whose segments cannot be borrowed after advancing.
+pub struct Recording<S: Sequence>
+where
+    S::Segment: RecordSegment,
+{
+    inner: S,
+    record: <S::Segment as RecordSegment>::Owned,
+}
+
+impl<S: Sequence> Recording<S>
+where
+    S::Segment: RecordSegment,
+{
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            record: Default::default(),
+        }
+    }
+
+    pub fn record(&self) -> &<S::Segment as RecordSegment>::Owned {
+        &self.record
+    }
+
+    pub fn into_parts(self) -> (<S::Segment as RecordSegment>::Owned, S) {
+        (self.record, self.inner)
+    }
+}
+
+impl<S> Sequence for Recording<S>
+where
+    S: Sequence,
+    S::Segment: RecordSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord + Sub<Output = S::Length>,
+{
+    type Length = S::Length;
+    type Segment = S::Segment;
+    type Segments<'a>
+        = S::Segments<'a>
+    where
+        Self: 'a;
+    type Advance = RecordingAdvance<S>;
+
+    fn segments(&mut self) -> Self::Segments<'_> {
+        self.inner.segments()
+    }
+
+    fn advance(self, delta: Self::Length) -> Self::Advance {
+        let start = <S::Segment as RecordSegment>::recorded_len(&self.record);
+
+        RecordingAdvance {
+            state: AdvanceState::Record {
+                sequence: self,
+                delta,
+                start,
+            },
+        }
+    }
+}
+
+pub struct RecordingAdvance<S: Sequence>
+where
+    S::Segment: RecordSegment,
+{
+    state: AdvanceState<S>,
+}
+
+enum AdvanceState<S: Sequence>
+where
+    S::Segment: RecordSegment,
+{
+    Record {
+        sequence: Recording<S>,
+        delta: S::Length,
+        start: <S::Segment as SequenceSegment>::Length,
+    },
+    Advance {
+        fut: <S::Advance as IntoFuture>::IntoFuture,
+        record: Option<<S::Segment as RecordSegment>::Owned>,
+    },
+    Done,
+}
+
+impl<S> Future for RecordingAdvance<S>
+where
+    S: Sequence,
+    S::Segment: RecordSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord + Sub<Output = S::Length>,
+{
+    type Output = Recording<S>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `Advance`の`fut`は完了するまで移動されない。`Record`は何もpinしない。
+        let this = unsafe { self.get_unchecked_mut() };
+
+        loop {
+            match &mut this.state {
+                AdvanceState::Record {
+                    sequence,
+                    delta,
+                    start,
+                } => {
+                    let delta = *delta;
+                    std::task::ready!(poll_record(sequence, delta, *start, cx));
+                    let AdvanceState::Record { sequence, .. } =
+                        std::mem::replace(&mut this.state, AdvanceState::Done)
+                    else {
+                        unreachable!()
+                    };
+
+                    this.state = AdvanceState::Advance {
+                        fut: sequence.inner.advance(delta).into_future(),
+                        record: Some(sequence.record),
+                    };
+                }
+                AdvanceState::Advance { fut, record } => {
+                    let inner = std::task::ready!(unsafe { Pin::new_unchecked(fut) }.poll(cx));
+                    let sequence = Recording {
+                        inner,
+                        record: record.take().unwrap(),
+                    };
+                    this.state = AdvanceState::Done;
+                    return Poll::Ready(sequence);
+                }
+                AdvanceState::Done => panic!("`RecordingAdvance` was polled after completion."),
+            }
+        }
+    }
+}
+
+/// copy the head of `inner` of `delta` length into the record after `start`.
+fn poll_record<S>(
+    sequence: &mut Recording<S>,
+    delta: S::Length,
+    start: S::Length,
+    cx: &mut Context<'_>,
+) -> Poll<()>
+where
+    S: Sequence,
+    S::Segment: RecordSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord + Sub<Output = S::Length>,
+{
+    // セグメントのストリームはpollをまたいで保持できないため、Pendingの場合は次回のpollで先頭から記録し直す。
+    <S::Segment as RecordSegment>::truncate(&mut sequence.record, start);
+    let mut remain = delta;
+    let mut segments = sequence.inner.segments();
+
+    while remain > Default::default() {
+        let next = std::pin::pin!(segments.next(remain));
+        match next.poll(cx) {
+            Poll::Ready(Some(segment)) => {
+                let len = segment.len();
+                if len >= remain {
+                    segment.split_at(remain).0.record(&mut sequence.record);
+                    break;
+                }
+                segment.record(&mut sequence.record);
+                remain = remain - len;
+            }
+            Poll::Ready(None) => break,
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+
+    Poll::Ready(())
+}
+
+pub struct RecordingAnchor<A, L> {
+    inner: A,
+    len: L,
+}
+
+impl<S> RewindSequence for Recording<S>
+where
+    Self: Sequence,
+    S: RewindSequence,
+    S::Segment: RecordSegment,
+{
+    type Anchor = RecordingAnchor<S::Anchor, <S::Segment as SequenceSegment>::Length>;
+    type Rewind = RecordingRewind<S>;
+
+    fn anchor(&self) -> Self::Anchor {
+        RecordingAnchor {
+            inner: self.inner.anchor(),
+            len: <S::Segment as RecordSegment>::recorded_len(&self.record),
+        }
+    }
+
+    fn rewind(self, anchor: Self::Anchor) -> Self::Rewind {
+        RecordingRewind {
+            fut: self.inner.rewind(anchor.inner),
+            rest: Some((self.record, anchor.len)),
+        }
+    }
+}
+
+pub struct RecordingRewind<S: RewindSequence>
+where
+    S::Segment: RecordSegment,
+{
+    fut: S::Rewind,
+    rest: Option<(Owned<S>, <S::Segment as SequenceSegment>::Length)>,
+}
+
+type Owned<S> = <<S as Sequence>::Segment as RecordSegment>::Owned;
+
+impl<S: RewindSequence> Future for RecordingRewind<S>
+where
+    S::Segment: RecordSegment,
+{
+    type Output = Recording<S>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`はpinされたまま移動されない。`rest`はpinされない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = std::task::ready!(unsafe { Pin::new_unchecked(&mut this.fut) }.poll(cx));
+        let (mut record, len) = this.rest.take().unwrap();
+        <S::Segment as RecordSegment>::truncate(&mut record, len);
+
+        Poll::Ready(Recording { inner, record })
+    }
+}
+
+impl<S> MeasuredSequence for Recording<S>
+where
+    Self: Sequence<Segment = S::Segment>,
+    S: MeasuredSequence,
+    S::Segment: RecordSegment,
+{
+    type Metrics = S::Metrics;
+
+    fn metrics(&self) -> Self::Metrics {
+        self.inner.metrics()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain;
+
+    #[test]
+    fn record_across_segments_and_rewind() {
+        pollster::block_on(async {
+            let sequence = Recording::new(chain("ab", "cd"));
+            let anchor = sequence.anchor();
+            let sequence = sequence.advance(BytesDelta::from_bytes(1)).await;
+            let sequence = sequence.advance(BytesDelta::from_bytes(2)).await;
+            assert_eq!(sequence.record(), "abc");
+
+            let sequence = sequence.rewind(anchor).await;
+            let sequence = sequence.advance(BytesDelta::from_bytes(4)).await;
+            let (record, _) = sequence.into_parts();
+            assert_eq!(record, "abcd");
+        })
+    }
+}
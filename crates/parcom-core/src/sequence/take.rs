@@ -0,0 +1,245 @@
+use crate::{MeasuredSequence, RewindSequence, SegmentStream, Sequence, SequenceSegment};
+use std::{
+    future::{Future, IntoFuture},
+    ops::Sub,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+pub fn take<S: Sequence>(sequence: S, limit: S::Length) -> Take<S> {
+    Take::new(sequence, limit)
+}
+
+/// sequence which ends after `limit` of `inner` has been read.
+///
+/// for `str` segments, `limit` must fall on a char boundary.
+#[derive(Debug)]
+pub struct Take<S: Sequence> {
+    inner: S,
+    remain: S::Length,
+}
+
+impl<S: Sequence> Take<S> {
+    pub fn new(inner: S, limit: S::Length) -> Self {
+        Self {
+            inner,
+            remain: limit,
+        }
+    }
+
+    /// return the length left before the end of this sequence.
+    pub fn remain(&self) -> &S::Length {
+        &self.remain
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Sequence for Take<S>
+where
+    S: Sequence,
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord + Sub<Output = S::Length>,
+{
+    type Length = S::Length;
+    type Segment = S::Segment;
+    type Segments<'a>
+        = TakeSegments<'a, S>
+    where
+        Self: 'a;
+    type Advance = TakeAdvance<S>;
+
+    fn segments(&mut self) -> Self::Segments<'_> {
+        TakeSegments {
+            inner: self.inner.segments(),
+            remain: self.remain,
+        }
+    }
+
+    fn advance(self, delta: Self::Length) -> Self::Advance {
+        let delta = delta.min(self.remain);
+        TakeAdvance {
+            fut: self.inner.advance(delta).into_future(),
+            remain: Some(self.remain - delta),
+        }
+    }
+}
+
+pub struct TakeSegments<'a, S: 'a + Sequence> {
+    inner: S::Segments<'a>,
+    remain: S::Length,
+}
+
+impl<'a, S> SegmentStream for TakeSegments<'a, S>
+where
+    S: 'a + Sequence,
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Copy + Default + Ord + Sub<Output = S::Length>,
+{
+    type Length = S::Length;
+    type Segment = S::Segment;
+    type Next<'b>
+        = TakeSegmentsNext<'b, S::Segments<'a>>
+    where
+        Self: 'b;
+
+    fn next(&mut self, size_hint: Self::Length) -> Self::Next<'_> {
+        let fut = if self.remain > Default::default() {
+            Some(self.inner.next(size_hint.min(self.remain)))
+        } else {
+            None
+        };
+
+        TakeSegmentsNext {
+            fut,
+            remain: &mut self.remain,
+        }
+    }
+}
+
+pub struct TakeSegmentsNext<'b, T: 'b + SegmentStream> {
+    fut: Option<T::Next<'b>>,
+    remain: &'b mut T::Length,
+}
+
+impl<'b, T> Future for TakeSegmentsNext<'b, T>
+where
+    T: 'b + SegmentStream,
+    T::Segment: SequenceSegment<Length = T::Length>,
+    T::Length: Copy + Default + Ord + Sub<Output = T::Length>,
+{
+    type Output = Option<&'b T::Segment>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`はpinされたまま移動されない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let Some(fut) = &mut this.fut else {
+            return Poll::Ready(None);
+        };
+
+        let Some(segment) = std::task::ready!(unsafe { Pin::new_unchecked(fut) }.poll(cx)) else {
+            return Poll::Ready(None);
+        };
+
+        let segment = if segment.len() > *this.remain {
+            segment.split_at(*this.remain).0
+        } else {
+            segment
+        };
+        *this.remain = *this.remain - segment.len();
+
+        Poll::Ready(Some(segment))
+    }
+}
+
+pub struct TakeAdvance<S: Sequence> {
+    fut: <S::Advance as IntoFuture>::IntoFuture,
+    remain: Option<S::Length>,
+}
+
+impl<S: Sequence> Future for TakeAdvance<S> {
+    type Output = Take<S>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`はpinされたまま移動されない。`remain`はpinされない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = std::task::ready!(unsafe { Pin::new_unchecked(&mut this.fut) }.poll(cx));
+
+        Poll::Ready(Take {
+            inner,
+            remain: this.remain.take().unwrap(),
+        })
+    }
+}
+
+pub struct TakeAnchor<A, L> {
+    inner: A,
+    remain: L,
+}
+
+impl<S> RewindSequence for Take<S>
+where
+    Self: Sequence,
+    S: RewindSequence,
+    S::Length: Copy,
+{
+    type Anchor = TakeAnchor<S::Anchor, S::Length>;
+    type Rewind = TakeRewind<S>;
+
+    fn anchor(&self) -> Self::Anchor {
+        TakeAnchor {
+            inner: self.inner.anchor(),
+            remain: self.remain,
+        }
+    }
+
+    fn rewind(self, anchor: Self::Anchor) -> Self::Rewind {
+        TakeRewind {
+            fut: self.inner.rewind(anchor.inner),
+            remain: Some(anchor.remain),
+        }
+    }
+}
+
+pub struct TakeRewind<S: RewindSequence> {
+    fut: S::Rewind,
+    remain: Option<S::Length>,
+}
+
+impl<S: RewindSequence> Future for TakeRewind<S> {
+    type Output = Take<S>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `fut`はpinされたまま移動されない。`remain`はpinされない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = std::task::ready!(unsafe { Pin::new_unchecked(&mut this.fut) }.poll(cx));
+
+        Poll::Ready(Take {
+            inner,
+            remain: this.remain.take().unwrap(),
+        })
+    }
+}
+
+impl<S> MeasuredSequence for Take<S>
+where
+    Self: Sequence<Segment = S::Segment>,
+    S: MeasuredSequence,
+{
+    type Metrics = S::Metrics;
+
+    fn metrics(&self) -> Self::Metrics {
+        self.inner.metrics()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain;
+
+    #[test]
+    fn stop_at_limit_across_segments() {
+        pollster::block_on(async {
+            let mut sequence = take(chain([0u8, 1].as_slice(), [2u8, 3].as_slice()), 3);
+
+            let mut buf = Vec::new();
+            let mut segments = sequence.segments();
+            while let Some(segment) = segments.next(0).await {
+                buf.extend_from_slice(segment);
+            }
+            assert_eq!(buf, [0, 1, 2]);
+
+            let anchor = sequence.anchor();
+            let sequence = sequence.advance(5).await;
+            assert_eq!(*sequence.remain(), 0);
+
+            let sequence = sequence.rewind(anchor).await;
+            assert_eq!(*sequence.remain(), 3);
+            let sequence = sequence.advance(2).await;
+            assert_eq!(sequence.into_inner().into_inner().1, [2, 3]);
+        })
+    }
+}
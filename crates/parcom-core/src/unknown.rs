@@ -10,6 +10,13 @@ impl<S> UnknownLocation<S> {
     pub fn as_ref(&self) -> UnknownLocation<&S> {
         UnknownLocation(&self.0)
     }
+    /// convert the sequence without revealing its location.
+    ///
+    /// # Safety
+    /// `f` must not let the sequence escape other than through its return value.
+    pub unsafe fn map<T>(self, f: impl FnOnce(S) -> T) -> UnknownLocation<T> {
+        UnknownLocation(f(self.0))
+    }
 }
 
 impl<S: RewindSequence> UnknownLocation<S> {
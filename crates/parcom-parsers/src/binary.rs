@@ -0,0 +1,7 @@
+pub mod bits;
+pub mod fixed_width;
+pub mod varint;
+
+pub use bits::{align, bits, flag, take_bits, Align, Bits, Flag, TakeBits, UnsignedBits};
+pub use fixed_width::{big_endian, little_endian, BinaryPrimitive, Endian, FixedWidth};
+pub use varint::{quic_varint, sleb128, uleb128, varint, zigzag_varint, VarintError};
@@ -0,0 +1,334 @@
+use parcom_core::{
+    BitOrder, BitSequence, Never, ParseError, ParseResult, Parser, ParserOnce, ParserResult,
+    SegmentStream, Sequence,
+};
+use parcom_util::{done, error::Miss, fail};
+use std::marker::PhantomData;
+
+/// run `parser` on the bits of the input, then skip the rest of the current byte.
+pub fn bits<S, P>(order: BitOrder, parser: P) -> Bits<S, P>
+where
+    S: Sequence<Segment = [u8], Length = usize>,
+    P: ParserOnce<BitSequence<S>>,
+{
+    Bits::new(order, parser)
+}
+
+/// read `count` bits as an unsigned integer.
+///
+/// panics if `count` exceeds the width of `T`.
+pub fn take_bits<T: UnsignedBits>(count: u32) -> TakeBits<T> {
+    TakeBits::new(count)
+}
+
+/// read a single bit.
+pub fn flag() -> Flag {
+    Flag::new()
+}
+
+/// skip to the start of the next byte unless the input is already at a byte boundary.
+pub fn align() -> Align {
+    Align::new()
+}
+
+#[derive(Debug)]
+pub struct Bits<S, P> {
+    order: BitOrder,
+    parser: P,
+    marker: PhantomData<S>,
+}
+
+impl<S, P> Bits<S, P>
+where
+    S: Sequence<Segment = [u8], Length = usize>,
+    P: ParserOnce<BitSequence<S>>,
+{
+    pub fn new(order: BitOrder, parser: P) -> Self {
+        Self {
+            order,
+            parser,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S, P> ParserOnce<S> for Bits<S, P>
+where
+    S: Sequence<Segment = [u8], Length = usize>,
+    P: ParserOnce<BitSequence<S>>,
+{
+    type Output = P::Output;
+    type Error = P::Error;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        let input = BitSequence::new(input, self.order);
+        finish(self.parser.parse_once(input).await).await
+    }
+}
+
+impl<S, P> Parser<S> for Bits<S, P>
+where
+    S: Sequence<Segment = [u8], Length = usize>,
+    P: Parser<BitSequence<S>>,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let input = BitSequence::new(input, self.order);
+        finish(self.parser.parse(input).await).await
+    }
+}
+
+async fn finish<S, O, E>(result: ParseResult<BitSequence<S>, O, E>) -> ParseResult<S, O, E>
+where
+    S: Sequence<Segment = [u8], Length = usize>,
+    E: ParseError,
+{
+    match result {
+        Ok((v, r)) => {
+            let r = skip_to_boundary(r).await;
+            done(v, r.into_inner())
+        }
+        // SAFETY: 位置は不明のまま、型だけを戻す。
+        Err((e, r)) => Err((e, unsafe { r.map(BitSequence::into_inner) })),
+    }
+}
+
+async fn skip_to_boundary<S>(input: BitSequence<S>) -> BitSequence<S>
+where
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    let rest = (8 - input.offset()) % 8;
+    input.advance(rest).await
+}
+
+/// unsigned integers read by `TakeBits`.
+pub trait UnsignedBits: Sized {
+    const BITS: u32;
+
+    /// `bits` must fit in `Self::BITS`.
+    fn from_bits(bits: u128) -> Self;
+}
+
+macro_rules! impl_unsigned_bits {
+    ($($t: ty),*) => {
+        $(
+            impl UnsignedBits for $t {
+                const BITS: u32 = <$t>::BITS;
+
+                fn from_bits(bits: u128) -> Self {
+                    bits as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_bits!(u8, u16, u32, u64, u128);
+
+#[derive(Debug)]
+pub struct TakeBits<T: UnsignedBits> {
+    count: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: UnsignedBits> TakeBits<T> {
+    pub fn new(count: u32) -> Self {
+        assert!(
+            count <= T::BITS,
+            "`count` exceeds the width of the output type."
+        );
+        Self {
+            count,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, S> ParserOnce<BitSequence<S>> for TakeBits<T>
+where
+    T: UnsignedBits,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    type Output = T;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: BitSequence<S>) -> ParserResult<BitSequence<S>, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, S> Parser<BitSequence<S>> for TakeBits<T>
+where
+    T: UnsignedBits,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    async fn parse(&self, mut input: BitSequence<S>) -> ParserResult<BitSequence<S>, Self> {
+        let count = self.count as usize;
+        let order = input.order();
+        let mut value = 0u128;
+        let mut read = 0;
+        let mut segments = input.segments();
+
+        while read < count {
+            let Some(segment) = segments.next(count - read).await else {
+                break;
+            };
+
+            for &bit in segment.iter().take(count - read) {
+                match order {
+                    BitOrder::MsbFirst => value = (value << 1) | bit as u128,
+                    BitOrder::LsbFirst => value |= (bit as u128) << read,
+                }
+                read += 1;
+            }
+        }
+
+        drop(segments);
+
+        if read < count {
+            return fail((), input);
+        }
+
+        done(T::from_bits(value), input.advance(count).await)
+    }
+}
+
+#[derive(Debug)]
+pub struct Flag {
+    _priv: (),
+}
+
+impl Flag {
+    pub fn new() -> Self {
+        Self { _priv: () }
+    }
+}
+
+impl Default for Flag {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Sequence<Segment = [u8], Length = usize>> ParserOnce<BitSequence<S>> for Flag {
+    type Output = bool;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: BitSequence<S>) -> ParserResult<BitSequence<S>, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<S: Sequence<Segment = [u8], Length = usize>> Parser<BitSequence<S>> for Flag {
+    async fn parse(&self, mut input: BitSequence<S>) -> ParserResult<BitSequence<S>, Self> {
+        let mut segments = input.segments();
+        let bit = loop {
+            match segments.next(1).await {
+                Some([bit, ..]) => break Some(*bit),
+                Some([]) => continue,
+                None => break None,
+            }
+        };
+
+        drop(segments);
+
+        match bit {
+            Some(bit) => done(bit, input.advance(1).await),
+            None => fail((), input),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Align {
+    _priv: (),
+}
+
+impl Align {
+    pub fn new() -> Self {
+        Self { _priv: () }
+    }
+}
+
+impl Default for Align {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Sequence<Segment = [u8], Length = usize>> ParserOnce<BitSequence<S>> for Align {
+    type Output = ();
+    type Error = Never;
+
+    async fn parse_once(self, input: BitSequence<S>) -> ParserResult<BitSequence<S>, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<S: Sequence<Segment = [u8], Length = usize>> Parser<BitSequence<S>> for Align {
+    async fn parse(&self, input: BitSequence<S>) -> ParserResult<BitSequence<S>, Self> {
+        done((), skip_to_boundary(input).await)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{binary::big_endian, ParserExtension};
+    use parcom_core::chain;
+
+    #[test]
+    fn parse_bitfields_then_bytes() {
+        pollster::block_on(async {
+            let input = [0b1010_0110u8, 0xFF].as_slice();
+            let parser =
+                bits(BitOrder::MsbFirst, take_bits::<u8>(3).join(flag())).join(big_endian::<u8>());
+            let (((v, f), byte), rest) = parser.parse(input).await.ok().unwrap();
+            assert_eq!((v, f, byte), (5, false, 0xFF));
+            assert_eq!(rest, []);
+
+            let input = chain([0x80u8, 0xFF].as_slice(), [0x80u8, 0x01].as_slice());
+            let parser = flag().join(align()).join(take_bits::<u16>(9));
+            let (((f, ()), v), rest) = bits(BitOrder::MsbFirst, parser)
+                .parse(input)
+                .await
+                .ok()
+                .unwrap();
+            assert!(f);
+            assert_eq!(v, 0x1FF);
+            assert_eq!(rest.into_inner().1, [0x01]);
+
+            let input = [0b0000_1101u8].as_slice();
+            let parser = take_bits::<u8>(3).join(take_bits::<u8>(2));
+            let ((v0, v1), _) = bits(BitOrder::LsbFirst, parser)
+                .parse(input)
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!((v0, v1), (0b101, 0b01));
+
+            let Err(_) = bits(BitOrder::MsbFirst, take_bits::<u16>(9))
+                .parse([0u8].as_slice())
+                .await
+            else {
+                panic!()
+            };
+        })
+    }
+
+    #[test]
+    fn parse_many_fields_from_large_input() {
+        pollster::block_on(async {
+            // 1フィールドごとに入力全体を複製すると終わらない大きさ。
+            let input = vec![0b0101_1010u8; 1 << 18];
+            let parser = take_bits::<u8>(4).repeat();
+            let ((fields, _), rest) = bits(BitOrder::MsbFirst, parser)
+                .parse(input.as_slice())
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(fields.len(), input.len() * 2);
+            assert!(fields.chunks(2).all(|f| f == [0b0101, 0b1010]));
+            assert_eq!(rest, []);
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use parcom_core::{Parser, ParserOnce, ParserResult, SegmentStream, Sequence};
+use parcom_util::{done, error::Miss, fail};
+use std::marker::PhantomData;
+
+/// read `T` in big endian.
+pub fn big_endian<T: BinaryPrimitive>() -> FixedWidth<T> {
+    FixedWidth::new(Endian::Big)
+}
+
+/// read `T` in little endian.
+pub fn little_endian<T: BinaryPrimitive>() -> FixedWidth<T> {
+    FixedWidth::new(Endian::Little)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+/// fixed-width primitive types read by `FixedWidth`.
+pub trait BinaryPrimitive: Sized {
+    /// byte array of the width of `Self`, e.g. `[u8; 4]` for `u32`.
+    type Bytes: Default + AsMut<[u8]>;
+
+    fn from_be_bytes(bytes: Self::Bytes) -> Self;
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+}
+
+macro_rules! impl_binary_primitive {
+    ($($t: ty),*) => {
+        $(
+            impl BinaryPrimitive for $t {
+                type Bytes = [u8; std::mem::size_of::<$t>()];
+
+                fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                    <$t>::from_be_bytes(bytes)
+                }
+
+                fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                    <$t>::from_le_bytes(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_primitive!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+#[derive(Debug)]
+pub struct FixedWidth<T: BinaryPrimitive> {
+    endian: Endian,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: BinaryPrimitive> FixedWidth<T> {
+    pub fn new(endian: Endian) -> Self {
+        Self {
+            endian,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, S> ParserOnce<S> for FixedWidth<T>
+where
+    T: BinaryPrimitive,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    type Output = T;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, S> Parser<S> for FixedWidth<T>
+where
+    T: BinaryPrimitive,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut bytes = T::Bytes::default();
+        let buf = bytes.as_mut();
+        let size = buf.len();
+        let mut filled = 0;
+        let mut segments = input.segments();
+
+        while filled < size {
+            let Some(segment) = segments.next(size - filled).await else {
+                break;
+            };
+
+            let n = segment.len().min(size - filled);
+            buf[filled..filled + n].copy_from_slice(&segment[..n]);
+            filled += n;
+        }
+
+        drop(segments);
+
+        if filled < size {
+            return fail((), input);
+        }
+
+        let value = match self.endian {
+            Endian::Big => T::from_be_bytes(bytes),
+            Endian::Little => T::from_le_bytes(bytes),
+        };
+
+        done(value, input.advance(size).await)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn read_across_segments() {
+        pollster::block_on(async {
+            let input = chain([0x12u8, 0x34, 0x56].as_slice(), [0x78u8, 0x9A].as_slice());
+
+            let (v, rest) = big_endian::<u32>().parse(input).await.ok().unwrap();
+            assert_eq!(v, 0x12345678);
+            assert_eq!(rest.into_inner().1, [0x9A]);
+
+            let input = chain([0x12u8].as_slice(), [0x34u8].as_slice());
+            let (v, _) = little_endian::<i16>().parse(input).await.ok().unwrap();
+            assert_eq!(v, 0x3412);
+
+            let bytes = 1.5f64.to_be_bytes();
+            let (v, _) = big_endian::<f64>()
+                .parse(bytes.as_slice())
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(v, 1.5);
+
+            assert!(big_endian::<u64>()
+                .parse([0u8; 7].as_slice())
+                .await
+                .is_err());
+        })
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Wide([u8; 32]);
+
+    impl BinaryPrimitive for Wide {
+        type Bytes = [u8; 32];
+
+        fn from_be_bytes(bytes: Self::Bytes) -> Self {
+            Self(bytes)
+        }
+
+        fn from_le_bytes(mut bytes: Self::Bytes) -> Self {
+            bytes.reverse();
+            Self(bytes)
+        }
+    }
+
+    #[test]
+    fn read_user_defined_width() {
+        pollster::block_on(async {
+            let bytes: Vec<u8> = (0..33).collect();
+            let (v, rest) = little_endian::<Wide>()
+                .parse(bytes.as_slice())
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(v.0[0], 31);
+            assert_eq!(rest, [32]);
+        })
+    }
+}
@@ -0,0 +1,400 @@
+use parcom_core::{Parser, ParserOnce, ParserResult, SegmentStream, Sequence};
+use parcom_util::{done, error::Miss, fail};
+use std::marker::PhantomData;
+
+/// unsigned LEB128.
+pub fn uleb128<T: UnsignedVarint>() -> Uleb128<T> {
+    Uleb128 {
+        marker: PhantomData,
+    }
+}
+
+/// signed LEB128.
+pub fn sleb128<T: SignedVarint>() -> Sleb128<T> {
+    Sleb128 {
+        marker: PhantomData,
+    }
+}
+
+/// protobuf varint. the same encoding as unsigned LEB128.
+pub fn varint<T: UnsignedVarint>() -> Uleb128<T> {
+    uleb128()
+}
+
+/// protobuf varint with zigzag encoding (`sint32`, `sint64`).
+pub fn zigzag_varint<T: SignedVarint>() -> ZigZagVarint<T> {
+    ZigZagVarint {
+        marker: PhantomData,
+    }
+}
+
+/// QUIC variable-length integer, whose length is given by the 2 most significant bits of the first byte.
+pub fn quic_varint() -> QuicVarint {
+    QuicVarint
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarintError {
+    /// the input ended before the last byte.
+    Truncated,
+    /// the value does not fit in the type, or the encoding has more bytes than the type needs.
+    Overflow,
+}
+
+pub trait UnsignedVarint: Sized {
+    const BITS: u32;
+
+    fn from_u128(value: u128) -> Option<Self>;
+}
+
+pub trait SignedVarint: Sized {
+    const BITS: u32;
+
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! impl_varint {
+    ($tr: ident, $f: ident, $v: ty; $($t: ty),*) => {
+        $(
+            impl $tr for $t {
+                const BITS: u32 = <$t>::BITS;
+
+                fn $f(value: $v) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_varint!(UnsignedVarint, from_u128, u128; u8, u16, u32, u64, u128, usize);
+impl_varint!(SignedVarint, from_i128, i128; i8, i16, i32, i64, i128, isize);
+
+/// bits of a LEB128 read into `u128`.
+struct Leb128 {
+    bits: u128,
+    /// count of bits read.
+    shift: u32,
+    /// whether the bits above 128 bits are all 0 or all 1.
+    upper_zeros: bool,
+    upper_ones: bool,
+    last: u8,
+    len: usize,
+}
+
+impl Leb128 {
+    fn unsigned(&self) -> Option<u128> {
+        self.upper_zeros.then_some(self.bits)
+    }
+
+    fn signed(&self) -> Option<i128> {
+        if self.shift < 128 {
+            let bits = if self.last & 0x40 != 0 {
+                self.bits | (!0 << self.shift)
+            } else {
+                self.bits
+            };
+            return Some(bits as i128);
+        }
+
+        let value = self.bits as i128;
+        let valid = if value < 0 {
+            self.upper_ones
+        } else {
+            self.upper_zeros
+        };
+        valid.then_some(value)
+    }
+}
+
+/// read a LEB128 of at most `ceil(bits / 7)` bytes.
+async fn read_leb128<S: Sequence<Segment = [u8], Length = usize>>(
+    input: &mut S,
+    bits: u32,
+) -> Result<Leb128, VarintError> {
+    let max_len = bits.div_ceil(7) as usize;
+    let mut leb = Leb128 {
+        bits: 0,
+        shift: 0,
+        upper_zeros: true,
+        upper_ones: true,
+        last: 0,
+        len: 0,
+    };
+    let mut segments = input.segments();
+
+    while let Some(segment) = segments.next(1).await {
+        for &b in segment {
+            if leb.len == max_len {
+                return Err(VarintError::Overflow);
+            }
+
+            let payload = (b & 0x7F) as u128;
+            leb.len += 1;
+            leb.last = b;
+
+            if leb.shift < 128 {
+                leb.bits |= payload << leb.shift;
+            }
+
+            // 128ビットを超える部分
+            let upper_len = (leb.shift + 7).saturating_sub(128).min(7);
+            if upper_len > 0 {
+                let upper = payload >> (7 - upper_len);
+                leb.upper_zeros &= upper == 0;
+                leb.upper_ones &= upper == (1 << upper_len) - 1;
+            }
+
+            leb.shift += 7;
+
+            if b & 0x80 == 0 {
+                return Ok(leb);
+            }
+        }
+    }
+
+    Err(VarintError::Truncated)
+}
+
+#[derive(Debug)]
+pub struct Uleb128<T: UnsignedVarint> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T, S> ParserOnce<S> for Uleb128<T>
+where
+    T: UnsignedVarint,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    type Output = T;
+    type Error = Miss<VarintError>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, S> Parser<S> for Uleb128<T>
+where
+    T: UnsignedVarint,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let leb = match read_leb128(&mut input, T::BITS).await {
+            Ok(v) => v,
+            Err(e) => return fail(e, input),
+        };
+
+        match leb.unsigned().and_then(T::from_u128) {
+            Some(v) => done(v, input.advance(leb.len).await),
+            None => fail(VarintError::Overflow, input),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Sleb128<T: SignedVarint> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T, S> ParserOnce<S> for Sleb128<T>
+where
+    T: SignedVarint,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    type Output = T;
+    type Error = Miss<VarintError>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, S> Parser<S> for Sleb128<T>
+where
+    T: SignedVarint,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let leb = match read_leb128(&mut input, T::BITS).await {
+            Ok(v) => v,
+            Err(e) => return fail(e, input),
+        };
+
+        match leb.signed().and_then(T::from_i128) {
+            Some(v) => done(v, input.advance(leb.len).await),
+            None => fail(VarintError::Overflow, input),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ZigZagVarint<T: SignedVarint> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T, S> ParserOnce<S> for ZigZagVarint<T>
+where
+    T: SignedVarint,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    type Output = T;
+    type Error = Miss<VarintError>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, S> Parser<S> for ZigZagVarint<T>
+where
+    T: SignedVarint,
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let leb = match read_leb128(&mut input, T::BITS).await {
+            Ok(v) => v,
+            Err(e) => return fail(e, input),
+        };
+
+        let value = leb
+            .unsigned()
+            .map(|n| ((n >> 1) as i128) ^ -((n & 1) as i128))
+            .and_then(T::from_i128);
+
+        match value {
+            Some(v) => done(v, input.advance(leb.len).await),
+            None => fail(VarintError::Overflow, input),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct QuicVarint;
+
+impl<S> ParserOnce<S> for QuicVarint
+where
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    type Output = u64;
+    type Error = Miss<VarintError>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<S> Parser<S> for QuicVarint
+where
+    S: Sequence<Segment = [u8], Length = usize>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut value = 0u64;
+        let mut len = None;
+        let mut read = 0;
+        let mut segments = input.segments();
+
+        'segments: while let Some(segment) = segments.next(1).await {
+            for &b in segment {
+                let len = *len.get_or_insert(1usize << (b >> 6));
+                let b = if read == 0 { b & 0x3F } else { b };
+                value = (value << 8) | b as u64;
+                read += 1;
+
+                if read == len {
+                    break 'segments;
+                }
+            }
+        }
+
+        drop(segments);
+
+        if len != Some(read) {
+            return fail(VarintError::Truncated, input);
+        }
+
+        done(value, input.advance(read).await)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn read_varints() {
+        pollster::block_on(async {
+            let input = chain([0xE5u8, 0x8E].as_slice(), [0x26u8, 0x01].as_slice());
+            let (v, rest) = uleb128::<u32>().parse(input).await.ok().unwrap();
+            assert_eq!(v, 624485);
+            assert_eq!(rest.into_inner().1, [0x01]);
+
+            let (v, _) = sleb128::<i32>()
+                .parse([0xC0u8, 0xBB, 0x78].as_slice())
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(v, -123456);
+
+            let bytes = [0xFFu8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F];
+            let (v, _) = sleb128::<i64>().parse(bytes.as_slice()).await.ok().unwrap();
+            assert_eq!(v, -1);
+
+            let bytes = [0xFFu8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+            assert!(sleb128::<i64>().parse(bytes.as_slice()).await.is_err());
+            let (v, _) = uleb128::<u64>().parse(bytes.as_slice()).await.ok().unwrap();
+            assert_eq!(v, u64::MAX);
+
+            let Err((e, _)) = uleb128::<u8>().parse([0x80u8, 0x02].as_slice()).await else {
+                panic!()
+            };
+            assert_eq!(e.0, VarintError::Overflow);
+
+            let Err((e, _)) = uleb128::<u64>().parse([0x80u8, 0x80].as_slice()).await else {
+                panic!()
+            };
+            assert_eq!(e.0, VarintError::Truncated);
+
+            let Err((e, _)) = uleb128::<u8>().parse([0x80u8, 0x80, 0x00].as_slice()).await else {
+                panic!()
+            };
+            assert_eq!(e.0, VarintError::Overflow);
+
+            let bytes = [0x80u8; 64];
+            let Err((e, _)) = sleb128::<i128>().parse(bytes.as_slice()).await else {
+                panic!()
+            };
+            assert_eq!(e.0, VarintError::Overflow);
+
+            let (v, _) = zigzag_varint::<i32>()
+                .parse([0x03u8].as_slice())
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(v, -2);
+            let bytes = [0xFEu8, 0xFF, 0xFF, 0xFF, 0x0F];
+            let (v, _) = zigzag_varint::<i32>()
+                .parse(bytes.as_slice())
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(v, i32::MAX);
+
+            let bytes = [0xC2u8, 0x19, 0x7C, 0x5E, 0xFF, 0x14, 0xE8, 0x8C];
+            let (v, _) = quic_varint().parse(bytes.as_slice()).await.ok().unwrap();
+            assert_eq!(v, 151288809941952652);
+            let (v, _) = quic_varint()
+                .parse([0x40u8, 0x25].as_slice())
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(v, 37);
+
+            let Err((e, _)) = quic_varint().parse([0x9Du8, 0x7F].as_slice()).await else {
+                panic!()
+            };
+            assert_eq!(e.0, VarintError::Truncated);
+        })
+    }
+}
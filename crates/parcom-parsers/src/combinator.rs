@@ -1,19 +1,47 @@
 pub mod and_then;
 pub mod bin_expr;
+pub mod choice;
+pub mod cut;
+pub mod delimited;
+pub mod dyn_parser;
 pub mod join;
+pub mod lookahead;
+pub mod many;
 pub mod map;
 pub mod optional;
 pub mod or;
+pub mod pratt;
+pub mod prefixed;
+pub mod recognize;
 pub mod reference;
+pub mod registry;
 pub mod repeat;
+pub mod rule;
+pub mod separated;
+pub mod seq;
 pub mod unify;
 
 pub use and_then::AndThen;
 pub use bin_expr::BinExprParser;
+pub use choice::{choice, Choice, ChoiceError};
+pub use cut::{cut, Cut};
+pub use delimited::{
+    delimited, preceded, terminated, Delimited, ItemUnits, Preceded, RecoverDelimited, Terminated,
+};
+pub use dyn_parser::DynParser;
 pub use join::Join;
+pub use lookahead::{lookahead, not, peek, Lookahead, Not, Peek};
+pub use many::{exactly, many, many1, many_m_n, Count, Many, ManyError};
 pub use map::{Map, MapErr};
 pub use optional::Optional;
 pub use or::Or;
+pub use pratt::{InfixOperator, PostfixOperator, PrattError, PrattParser, PrefixOperator};
+pub use prefixed::{count_prefixed, length_prefixed, CountPrefixed, LengthPrefixed};
+pub use recognize::Recognize;
 pub use reference::Ref;
+pub use registry::Registry;
 pub use repeat::Repeat;
+pub use rule::{recursive, Rule, WeakRule};
+pub use separated::{separated_by, separated_by1, terminated_by, SeparatedBy};
+pub use seq::{seq, Seq};
 pub use unify::{Unify, UnifyErr};
@@ -0,0 +1,160 @@
+use parcom_core::{ParseError, ParseResult, Parser, ParserOnce, ParserResult, RewindSequence};
+use parcom_util::{done, fail, Either2, Either3, Either4, Either5, Either6, Either7, Either8};
+use std::{future::Future, marker::PhantomData};
+
+/// try each parser of the tuple `parsers` in order and return the output of the first success.
+pub fn choice<S: RewindSequence, T: ChoiceParsersOnce<S>>(parsers: T) -> Choice<S, T> {
+    Choice::new(parsers)
+}
+
+#[derive(Debug)]
+pub enum ChoiceError<E, A> {
+    /// a parser failed with an error which should terminate.
+    Terminated(E),
+    /// all parsers failed. holds the errors in the order of the parsers.
+    Missed(A),
+}
+
+impl<E: ParseError, A> ParseError for ChoiceError<E, A> {
+    fn should_terminate(&self) -> bool {
+        match self {
+            ChoiceError::Terminated(e) => e.should_terminate(),
+            ChoiceError::Missed(_) => false,
+        }
+    }
+}
+
+/// tuple of parsers tried by `Choice`.
+pub trait ChoiceParsersOnce<S: RewindSequence> {
+    type Output;
+    type Error: ParseError;
+
+    fn parse_choice_once(
+        self,
+        input: S,
+    ) -> impl Future<Output = ParseResult<S, Self::Output, Self::Error>>;
+}
+
+pub trait ChoiceParsers<S: RewindSequence>: ChoiceParsersOnce<S> {
+    fn parse_choice(
+        &self,
+        input: S,
+    ) -> impl Future<Output = ParseResult<S, Self::Output, Self::Error>>;
+}
+
+macro_rules! impl_choice_parsers {
+    ($($either: ident { $($p: ident $i: tt $v: ident $e: ident),* })*) => {
+        $(
+            impl<S: RewindSequence, $($p: ParserOnce<S>),*> ChoiceParsersOnce<S> for ($($p,)*) {
+                type Output = $either<$(<$p as ParserOnce<S>>::Output),*>;
+                type Error = ChoiceError<
+                    $either<$(<$p as ParserOnce<S>>::Error),*>,
+                    ($(<$p as ParserOnce<S>>::Error,)*),
+                >;
+
+                async fn parse_choice_once(
+                    self,
+                    input: S,
+                ) -> ParseResult<S, Self::Output, Self::Error> {
+                    let mut input = input;
+                    $(
+                        let anchor = input.anchor();
+                        let $e = match self.$i.parse_once(input).await {
+                            Ok((v, r)) => return done($either::$v(v), r),
+                            Err((e, r)) if !e.should_terminate() => {
+                                input = r.rewind(anchor).await;
+                                e
+                            }
+                            Err((e, r)) => return fail(ChoiceError::Terminated($either::$v(e)), r),
+                        };
+                    )*
+
+                    fail(ChoiceError::Missed(($($e,)*)), input)
+                }
+            }
+
+            impl<S: RewindSequence, $($p: Parser<S>),*> ChoiceParsers<S> for ($($p,)*) {
+                async fn parse_choice(
+                    &self,
+                    input: S,
+                ) -> ParseResult<S, Self::Output, Self::Error> {
+                    let mut input = input;
+                    $(
+                        let anchor = input.anchor();
+                        let $e = match self.$i.parse(input).await {
+                            Ok((v, r)) => return done($either::$v(v), r),
+                            Err((e, r)) if !e.should_terminate() => {
+                                input = r.rewind(anchor).await;
+                                e
+                            }
+                            Err((e, r)) => return fail(ChoiceError::Terminated($either::$v(e)), r),
+                        };
+                    )*
+
+                    fail(ChoiceError::Missed(($($e,)*)), input)
+                }
+            }
+        )*
+    };
+}
+
+impl_choice_parsers! {
+    Either2 { P0 0 V0 e0, P1 1 V1 e1 }
+    Either3 { P0 0 V0 e0, P1 1 V1 e1, P2 2 V2 e2 }
+    Either4 { P0 0 V0 e0, P1 1 V1 e1, P2 2 V2 e2, P3 3 V3 e3 }
+    Either5 { P0 0 V0 e0, P1 1 V1 e1, P2 2 V2 e2, P3 3 V3 e3, P4 4 V4 e4 }
+    Either6 { P0 0 V0 e0, P1 1 V1 e1, P2 2 V2 e2, P3 3 V3 e3, P4 4 V4 e4, P5 5 V5 e5 }
+    Either7 { P0 0 V0 e0, P1 1 V1 e1, P2 2 V2 e2, P3 3 V3 e3, P4 4 V4 e4, P5 5 V5 e5, P6 6 V6 e6 }
+    Either8 { P0 0 V0 e0, P1 1 V1 e1, P2 2 V2 e2, P3 3 V3 e3, P4 4 V4 e4, P5 5 V5 e5, P6 6 V6 e6, P7 7 V7 e7 }
+}
+
+#[derive(Debug)]
+pub struct Choice<S: RewindSequence, T: ChoiceParsersOnce<S>> {
+    parsers: T,
+    marker: PhantomData<S>,
+}
+
+impl<S: RewindSequence, T: ChoiceParsersOnce<S>> Choice<S, T> {
+    pub fn new(parsers: T) -> Self {
+        Self {
+            parsers,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: RewindSequence, T: ChoiceParsersOnce<S>> ParserOnce<S> for Choice<S, T> {
+    type Output = T::Output;
+    type Error = T::Error;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parsers.parse_choice_once(input).await
+    }
+}
+
+impl<S: RewindSequence, T: ChoiceParsers<S>> Parser<S> for Choice<S, T> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        self.parsers.parse_choice(input).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::atom;
+
+    #[test]
+    fn choose_first_success() {
+        pollster::block_on(async {
+            let parser = choice((atom("let"), atom("fn"), atom("if")));
+
+            let (v, rest) = parser.parse("fn main").await.ok().unwrap();
+            let Either3::V1(_) = v else { panic!() };
+            assert_eq!(rest, " main");
+
+            let Err((ChoiceError::Missed(_), _)) = parser.parse("loop").await else {
+                panic!()
+            };
+        })
+    }
+}
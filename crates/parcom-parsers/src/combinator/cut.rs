@@ -0,0 +1,70 @@
+use parcom_core::{Parser, ParserOnce, ParserResult, Sequence};
+use parcom_util::error::Fatal;
+use std::marker::PhantomData;
+
+/// make every error of `parser` terminal, so that `or`, `optional` and `repeat` do not backtrack over it.
+pub fn cut<S: Sequence, P: ParserOnce<S>>(parser: P) -> Cut<S, P> {
+    Cut::new(parser)
+}
+
+#[derive(Debug)]
+pub struct Cut<S: Sequence, P: ParserOnce<S>> {
+    parser: P,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, P: ParserOnce<S>> Cut<S, P> {
+    pub fn new(parser: P) -> Self {
+        Self {
+            parser,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: Sequence, P: ParserOnce<S>> ParserOnce<S> for Cut<S, P> {
+    type Output = P::Output;
+    type Error = Fatal<P::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parser
+            .parse_once(input)
+            .await
+            .map_err(|(e, r)| (Fatal(e), r))
+    }
+}
+
+impl<S: Sequence, P: Parser<S>> Parser<S> for Cut<S, P> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        self.parser
+            .parse(input)
+            .await
+            .map_err(|(e, r)| (Fatal(e), r))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        primitive::{atom, the_char},
+        ParserExtension,
+    };
+    use parcom_core::{ParseError, Parser};
+
+    #[test]
+    fn stop_backtracking_after_prefix() {
+        pollster::block_on(async {
+            let call = atom("f").then_cut(the_char('('));
+            let parser = call.or(atom("f"));
+
+            assert!(parser.parse("f(").await.is_ok());
+            let Err((e, _)) = parser.parse("f)").await else {
+                panic!()
+            };
+            assert!(e.should_terminate());
+
+            let parser = atom("g").then_cut(the_char('(')).or(atom("f"));
+            assert!(parser.parse("f").await.is_ok());
+        })
+    }
+}
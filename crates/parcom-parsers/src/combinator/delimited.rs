@@ -0,0 +1,404 @@
+use crate::primitive::atom_set::AtomUnits;
+use parcom_core::{
+    ParseError, Parser, ParserOnce, ParserResult, RewindSequence, SegmentStream, Sequence,
+};
+use parcom_util::{done, fail, Either, Either3};
+use std::marker::PhantomData;
+
+/// run `open`, `inner` and `close` in order and return the output of `inner`.
+pub fn delimited<S, O, P, C>(open: O, inner: P, close: C) -> Delimited<S, O, P, C>
+where
+    S: Sequence,
+    O: ParserOnce<S>,
+    P: ParserOnce<S>,
+    C: ParserOnce<S>,
+{
+    Delimited::new(open, inner, close)
+}
+
+/// run `prefix` and `parser` in order and return the output of `parser`.
+pub fn preceded<S, Q, P>(prefix: Q, parser: P) -> Preceded<S, Q, P>
+where
+    S: Sequence,
+    Q: ParserOnce<S>,
+    P: ParserOnce<S>,
+{
+    Preceded::new(prefix, parser)
+}
+
+/// run `parser` and `suffix` in order and return the output of `parser`.
+pub fn terminated<S, P, Q>(parser: P, suffix: Q) -> Terminated<S, P, Q>
+where
+    S: Sequence,
+    P: ParserOnce<S>,
+    Q: ParserOnce<S>,
+{
+    Terminated::new(parser, suffix)
+}
+
+#[derive(Debug)]
+pub struct Delimited<S: Sequence, O: ParserOnce<S>, P: ParserOnce<S>, C: ParserOnce<S>> {
+    open: O,
+    inner: P,
+    close: C,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, O: ParserOnce<S>, P: ParserOnce<S>, C: ParserOnce<S>> Delimited<S, O, P, C> {
+    pub fn new(open: O, inner: P, close: C) -> Self {
+        Self {
+            open,
+            inner,
+            close,
+            marker: PhantomData,
+        }
+    }
+
+    /// on a failure of `inner` or of `close` after `inner`, skip to the matching `close` and return the error as the output.
+    pub fn recover(self) -> RecoverDelimited<S, O, P, C>
+    where
+        S: RewindSequence,
+        O: Parser<S>,
+        P: Parser<S>,
+        C: Parser<S>,
+        S::Segment: ItemUnits<Length = S::Length>,
+        S::Length: Default,
+    {
+        RecoverDelimited { delimited: self }
+    }
+}
+
+impl<S: Sequence, O: ParserOnce<S>, P: ParserOnce<S>, C: ParserOnce<S>> ParserOnce<S>
+    for Delimited<S, O, P, C>
+{
+    type Output = P::Output;
+    type Error = Either3<O::Error, P::Error, C::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        let (_, rest) = self
+            .open
+            .parse_once(input)
+            .await
+            .map_err(|(e, r)| (Either3::V0(e), r))?;
+        let (v, rest) = self
+            .inner
+            .parse_once(rest)
+            .await
+            .map_err(|(e, r)| (Either3::V1(e), r))?;
+        let (_, rest) = self
+            .close
+            .parse_once(rest)
+            .await
+            .map_err(|(e, r)| (Either3::V2(e), r))?;
+
+        done(v, rest)
+    }
+}
+
+impl<S: Sequence, O: Parser<S>, P: Parser<S>, C: Parser<S>> Parser<S> for Delimited<S, O, P, C> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let (_, rest) = self
+            .open
+            .parse(input)
+            .await
+            .map_err(|(e, r)| (Either3::V0(e), r))?;
+        let (v, rest) = self
+            .inner
+            .parse(rest)
+            .await
+            .map_err(|(e, r)| (Either3::V1(e), r))?;
+        let (_, rest) = self
+            .close
+            .parse(rest)
+            .await
+            .map_err(|(e, r)| (Either3::V2(e), r))?;
+
+        done(v, rest)
+    }
+}
+
+/// units of which an item of the sequence starts, e.g. the leading byte of a char of `str`.
+pub trait ItemUnits: AtomUnits {
+    fn starts_item(unit: &Self::Unit) -> bool;
+}
+
+impl ItemUnits for str {
+    fn starts_item(unit: &u8) -> bool {
+        // UTF-8の継続バイトは0b10xx_xxxx。
+        (*unit as i8) >= -0x40
+    }
+}
+
+impl<T: 'static + Clone + PartialEq> ItemUnits for [T] {
+    fn starts_item(_: &T) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct RecoverDelimited<S: Sequence, O: ParserOnce<S>, P: ParserOnce<S>, C: ParserOnce<S>> {
+    delimited: Delimited<S, O, P, C>,
+}
+
+impl<S, O, P, C> ParserOnce<S> for RecoverDelimited<S, O, P, C>
+where
+    S: RewindSequence,
+    S::Segment: ItemUnits<Length = S::Length>,
+    S::Length: Default,
+    O: Parser<S>,
+    P: Parser<S>,
+    C: Parser<S>,
+{
+    /// `Err` holds the error of `inner` or `close` which was recovered from.
+    type Output = Result<P::Output, Either<P::Error, C::Error>>;
+    type Error = Either3<O::Error, P::Error, C::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<S, O, P, C> Parser<S> for RecoverDelimited<S, O, P, C>
+where
+    S: RewindSequence,
+    S::Segment: ItemUnits<Length = S::Length>,
+    S::Length: Default,
+    O: Parser<S>,
+    P: Parser<S>,
+    C: Parser<S>,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let Delimited {
+            open, inner, close, ..
+        } = &self.delimited;
+
+        let (_, rest) = open
+            .parse(input)
+            .await
+            .map_err(|(e, r)| (Either3::V0(e), r))?;
+
+        let anchor = rest.anchor();
+        let (err, mut rest) = match inner.parse(rest).await {
+            Ok((v, r)) => {
+                // `inner`の後に`close`がなければ、そこから読み飛ばす。
+                let anchor = r.anchor();
+                match close.parse(r).await {
+                    Ok((_, r)) => return done(Ok(v), r),
+                    Err((e, r)) if e.should_terminate() => return fail(Either3::V2(e), r),
+                    Err((e, r)) => (Either::Last(e), r.rewind(anchor).await),
+                }
+            }
+            Err((e, r)) if e.should_terminate() => return fail(Either3::V1(e), r),
+            Err((e, r)) => (Either::First(e), r.rewind(anchor).await),
+        };
+
+        let mut depth = 0usize;
+
+        // `open`の直後から、入れ子を数えながら対応する`close`まで読み飛ばす。
+        loop {
+            let anchor = rest.anchor();
+            let close_err = match close.parse(rest).await {
+                Ok((_, r)) if depth == 0 => return done(Err(err), r),
+                Ok((_, r)) => {
+                    depth -= 1;
+                    rest = r;
+                    continue;
+                }
+                Err((e, r)) if e.should_terminate() => return fail(Either3::V2(e), r),
+                Err((e, r)) => {
+                    rest = r.rewind(anchor).await;
+                    e
+                }
+            };
+
+            let anchor = rest.anchor();
+            match open.parse(rest).await {
+                Ok((_, r)) => {
+                    depth += 1;
+                    rest = r;
+                    continue;
+                }
+                Err((e, r)) if e.should_terminate() => return fail(Either3::V0(e), r),
+                Err((_, r)) => rest = r.rewind(anchor).await,
+            }
+
+            let mut segments = rest.segments();
+            let unit = loop {
+                match segments.next(Default::default()).await {
+                    Some(segment) => match segment.units().split_first() {
+                        Some((_, tail)) => {
+                            let n = 1 + tail
+                                .iter()
+                                .take_while(|u| !S::Segment::starts_item(u))
+                                .count();
+                            break Some(S::Segment::units_len(n));
+                        }
+                        None => continue,
+                    },
+                    None => break None,
+                }
+            };
+            drop(segments);
+
+            match unit {
+                Some(unit) => rest = rest.advance(unit).await,
+                None => return fail(Either3::V2(close_err), rest),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Preceded<S: Sequence, Q: ParserOnce<S>, P: ParserOnce<S>> {
+    prefix: Q,
+    parser: P,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, Q: ParserOnce<S>, P: ParserOnce<S>> Preceded<S, Q, P> {
+    pub fn new(prefix: Q, parser: P) -> Self {
+        Self {
+            prefix,
+            parser,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: Sequence, Q: ParserOnce<S>, P: ParserOnce<S>> ParserOnce<S> for Preceded<S, Q, P> {
+    type Output = P::Output;
+    type Error = Either<Q::Error, P::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        let (_, rest) = self
+            .prefix
+            .parse_once(input)
+            .await
+            .map_err(|(e, r)| (Either::First(e), r))?;
+        self.parser
+            .parse_once(rest)
+            .await
+            .map_err(|(e, r)| (Either::Last(e), r))
+    }
+}
+
+impl<S: Sequence, Q: Parser<S>, P: Parser<S>> Parser<S> for Preceded<S, Q, P> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let (_, rest) = self
+            .prefix
+            .parse(input)
+            .await
+            .map_err(|(e, r)| (Either::First(e), r))?;
+        self.parser
+            .parse(rest)
+            .await
+            .map_err(|(e, r)| (Either::Last(e), r))
+    }
+}
+
+#[derive(Debug)]
+pub struct Terminated<S: Sequence, P: ParserOnce<S>, Q: ParserOnce<S>> {
+    parser: P,
+    suffix: Q,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, P: ParserOnce<S>, Q: ParserOnce<S>> Terminated<S, P, Q> {
+    pub fn new(parser: P, suffix: Q) -> Self {
+        Self {
+            parser,
+            suffix,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: Sequence, P: ParserOnce<S>, Q: ParserOnce<S>> ParserOnce<S> for Terminated<S, P, Q> {
+    type Output = P::Output;
+    type Error = Either<P::Error, Q::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        let (v, rest) = self
+            .parser
+            .parse_once(input)
+            .await
+            .map_err(|(e, r)| (Either::First(e), r))?;
+        let (_, rest) = self
+            .suffix
+            .parse_once(rest)
+            .await
+            .map_err(|(e, r)| (Either::Last(e), r))?;
+
+        done(v, rest)
+    }
+}
+
+impl<S: Sequence, P: Parser<S>, Q: Parser<S>> Parser<S> for Terminated<S, P, Q> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let (v, rest) = self
+            .parser
+            .parse(input)
+            .await
+            .map_err(|(e, r)| (Either::First(e), r))?;
+        let (_, rest) = self
+            .suffix
+            .parse(rest)
+            .await
+            .map_err(|(e, r)| (Either::Last(e), r))?;
+
+        done(v, rest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        combinator::many1,
+        primitive::{atom, digit, the_char},
+        ParserExtension,
+    };
+
+    #[test]
+    fn recover_to_matching_close() {
+        pollster::block_on(async {
+            let parser = delimited(the_char('('), many1(digit()), the_char(')'));
+            let (v, rest) = parser.parse("(12)+").await.ok().unwrap();
+            assert_eq!(v, ['1', '2']);
+            assert_eq!(rest, "+");
+            assert!(parser.parse("(a)").await.is_err());
+
+            let parser = delimited(the_char('('), many1(digit()), the_char(')')).recover();
+            let (v, rest) = parser.parse("(x(y)z)+").await.ok().unwrap();
+            let Err(Either::First(_)) = v else { panic!() };
+            assert_eq!(rest, "+");
+
+            let (v, rest) = parser.parse("(12x)+").await.ok().unwrap();
+            let Err(Either::Last(_)) = v else { panic!() };
+            assert_eq!(rest, "+");
+
+            let parser = delimited(atom("<<"), many1(digit()), atom(">>")).recover();
+            let (v, rest) = parser.parse("<<1<<é>>2>>+").await.ok().unwrap();
+            let Err(Either::Last(_)) = v else { panic!() };
+            assert_eq!(rest, "+");
+
+            // 読み飛ばしている間の致命的なエラーはそのまま返す。
+            let close = the_char(')').join(the_char(';').cut());
+            let parser = delimited(the_char('('), many1(digit()), close).recover();
+            let (v, rest) = parser.parse("(x);").await.ok().unwrap();
+            assert!(v.is_err());
+            assert_eq!(rest, "");
+            let Err((Either3::V2(_), _)) = parser.parse("(x)+").await else {
+                panic!()
+            };
+
+            let Err((Either3::V2(_), _)) = parser.parse("(x(y)").await else {
+                panic!()
+            };
+
+            let parser = preceded(the_char('-'), terminated(digit(), the_char(';')));
+            let (v, rest) = parser.parse("-1;").await.ok().unwrap();
+            assert_eq!((v, rest), ('1', ""));
+        })
+    }
+}
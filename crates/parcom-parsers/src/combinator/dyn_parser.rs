@@ -0,0 +1,81 @@
+use parcom_core::{ParseError, ParseResult, Parser, ParserOnce, ParserResult, Sequence};
+use std::{future::Future, marker::PhantomData, pin::Pin};
+
+/// object-safe counterpart of `Parser`.
+trait ErasedParser<S, O, E> {
+    fn parse_boxed<'a>(
+        &'a self,
+        input: S,
+    ) -> Pin<Box<dyn 'a + Future<Output = ParseResult<S, O, E>>>>
+    where
+        S: 'a;
+}
+
+impl<S: Sequence, P: Parser<S>> ErasedParser<S, P::Output, P::Error> for P {
+    fn parse_boxed<'a>(&'a self, input: S) -> Pin<Box<dyn 'a + Future<Output = ParserResult<S, P>>>>
+    where
+        S: 'a,
+    {
+        Box::pin(self.parse(input))
+    }
+}
+
+/// parser whose type is erased. the futures it returns are boxed.
+pub struct DynParser<'p, S, O, E> {
+    parser: Box<dyn 'p + ErasedParser<S, O, E>>,
+    marker: PhantomData<fn() -> (O, E)>,
+}
+
+impl<'p, S: Sequence, O, E: ParseError> DynParser<'p, S, O, E> {
+    pub fn new<P: 'p + Parser<S, Output = O, Error = E>>(parser: P) -> Self {
+        Self {
+            parser: Box::new(parser),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'p, S, O, E> std::fmt::Debug for DynParser<'p, S, O, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynParser").finish_non_exhaustive()
+    }
+}
+
+impl<'p, S: Sequence, O, E: ParseError> ParserOnce<S> for DynParser<'p, S, O, E> {
+    type Output = O;
+    type Error = E;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<'p, S: Sequence, O, E: ParseError> Parser<S> for DynParser<'p, S, O, E> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        self.parser.parse_boxed(input).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        primitive::{atom, digit},
+        ParserExtension,
+    };
+    use parcom_util::error::Miss;
+
+    #[test]
+    fn store_parsers_of_different_types() {
+        pollster::block_on(async {
+            let parsers: Vec<DynParser<&str, char, Miss<()>>> = vec![
+                digit().into_dyn(),
+                atom("x").map(|_| 'x').map_err(|_| Miss(())).into_dyn(),
+            ];
+
+            let (c, rest) = parsers[1].parse("xy").await.ok().unwrap();
+            assert_eq!((c, rest), ('x', "y"));
+            assert!(parsers[0].parse("xy").await.is_err());
+        })
+    }
+}
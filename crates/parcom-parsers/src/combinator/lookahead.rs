@@ -0,0 +1,178 @@
+use parcom_core::{ParseError, Parser, ParserOnce, ParserResult, PeekableSequence, RewindSequence};
+use parcom_util::{done, error::Miss, fail, Either};
+use std::marker::PhantomData;
+
+/// run `parser` and return its output without consuming the input.
+pub fn peek<S: RewindSequence, P: ParserOnce<S>>(parser: P) -> Peek<S, P> {
+    Peek::new(parser)
+}
+
+/// succeed without consuming the input if `parser` fails.
+pub fn not<S: RewindSequence, P: ParserOnce<S>>(parser: P) -> Not<S, P> {
+    Not::new(parser)
+}
+
+/// same as `peek`, but run `parser` on a peek of the input instead of rewinding.
+pub fn lookahead<S, P, O, E>(parser: P) -> Lookahead<S, P>
+where
+    S: PeekableSequence,
+    P: for<'a> ParserOnce<S::Peek<'a>, Output = O, Error = E>,
+    E: ParseError,
+{
+    Lookahead::new(parser)
+}
+
+#[derive(Debug)]
+pub struct Peek<S: RewindSequence, P: ParserOnce<S>> {
+    parser: P,
+    marker: PhantomData<S>,
+}
+
+impl<S: RewindSequence, P: ParserOnce<S>> Peek<S, P> {
+    pub fn new(parser: P) -> Self {
+        Self {
+            parser,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: RewindSequence, P: ParserOnce<S>> ParserOnce<S> for Peek<S, P> {
+    type Output = P::Output;
+    type Error = P::Error;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        let anchor = input.anchor();
+        match self.parser.parse_once(input).await {
+            Ok((v, r)) => done(v, r.rewind(anchor).await),
+            Err((e, r)) => fail(e, r),
+        }
+    }
+}
+
+impl<S: RewindSequence, P: Parser<S>> Parser<S> for Peek<S, P> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let anchor = input.anchor();
+        match self.parser.parse(input).await {
+            Ok((v, r)) => done(v, r.rewind(anchor).await),
+            Err((e, r)) => fail(e, r),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Not<S: RewindSequence, P: ParserOnce<S>> {
+    parser: P,
+    marker: PhantomData<S>,
+}
+
+impl<S: RewindSequence, P: ParserOnce<S>> Not<S, P> {
+    pub fn new(parser: P) -> Self {
+        Self {
+            parser,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: RewindSequence, P: ParserOnce<S>> ParserOnce<S> for Not<S, P> {
+    type Output = ();
+    /// `First` holds the output of `parser` if it succeeded.
+    type Error = Either<Miss<P::Output>, P::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        let anchor = input.anchor();
+        match self.parser.parse_once(input).await {
+            Ok((v, r)) => fail(Either::First(Miss(v)), r.rewind(anchor).await),
+            Err((e, r)) if e.should_terminate() => fail(Either::Last(e), r),
+            Err((_, r)) => done((), r.rewind(anchor).await),
+        }
+    }
+}
+
+impl<S: RewindSequence, P: Parser<S>> Parser<S> for Not<S, P> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let anchor = input.anchor();
+        match self.parser.parse(input).await {
+            Ok((v, r)) => fail(Either::First(Miss(v)), r.rewind(anchor).await),
+            Err((e, r)) if e.should_terminate() => fail(Either::Last(e), r),
+            Err((_, r)) => done((), r.rewind(anchor).await),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Lookahead<S, P> {
+    parser: P,
+    marker: PhantomData<S>,
+}
+
+impl<S, P> Lookahead<S, P> {
+    pub fn new(parser: P) -> Self {
+        Self {
+            parser,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S, P, O, E> ParserOnce<S> for Lookahead<S, P>
+where
+    S: PeekableSequence,
+    P: for<'a> ParserOnce<S::Peek<'a>, Output = O, Error = E>,
+    E: ParseError,
+{
+    type Output = O;
+    type Error = E;
+
+    async fn parse_once(self, mut input: S) -> ParserResult<S, Self> {
+        // 入力は進めないため、失敗した場合も位置は既知である。
+        let result = self.parser.parse_once(input.peek()).await;
+        match result.map(|(v, _)| v).map_err(|(e, _)| e) {
+            Ok(v) => done(v, input),
+            Err(e) => fail(e, input),
+        }
+    }
+}
+
+impl<S, P, O, E> Parser<S> for Lookahead<S, P>
+where
+    S: PeekableSequence,
+    P: for<'a> Parser<S::Peek<'a>, Output = O, Error = E>,
+    E: ParseError,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let result = self.parser.parse(input.peek()).await;
+        match result.map(|(v, _)| v).map_err(|(e, _)| e) {
+            Ok(v) => done(v, input),
+            Err(e) => fail(e, input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        primitive::{the_char, xid_continue},
+        ParserExtension,
+    };
+
+    #[test]
+    fn assert_without_consuming() {
+        pollster::block_on(async {
+            let ident = xid_continue().repeat().join(not(the_char('(')));
+            let ((_, ()), rest) = ident.parse("abc + 1").await.ok().unwrap();
+            assert_eq!(rest, " + 1");
+            let Err((Either::Last(Either::First(_)), _)) = ident.parse("abc(1)").await else {
+                panic!()
+            };
+
+            let (c, rest) = peek(xid_continue()).parse("a").await.ok().unwrap();
+            assert_eq!((c, rest), ('a', "a"));
+
+            let (c, rest) = lookahead(the_char('a')).parse("ab").await.ok().unwrap();
+            assert_eq!((c, rest), ((), "ab"));
+        })
+    }
+}
@@ -0,0 +1,228 @@
+use parcom_core::{
+    IterativeParser, IterativeParserOnce, IterativeParserState, ParseError, ParseResult, Parser,
+    ParserOnce, ParserResult, RewindSequence, Sequence,
+};
+use parcom_util::{done, fail};
+use std::marker::PhantomData;
+
+use super::Ref;
+
+/// zero or more `item`s.
+pub fn many<S: RewindSequence, P: Parser<S>>(item: P) -> Many<S, P> {
+    Many::new(item, 0, usize::MAX)
+}
+
+/// one or more `item`s.
+pub fn many1<S: RewindSequence, P: Parser<S>>(item: P) -> Many<S, P> {
+    Many::new(item, 1, usize::MAX)
+}
+
+/// at least `min` and at most `max` `item`s.
+///
+/// panics if `min` is greater than `max`.
+pub fn many_m_n<S: RewindSequence, P: Parser<S>>(item: P, min: usize, max: usize) -> Many<S, P> {
+    assert!(min <= max, "`min` is greater than `max`.");
+    Many::new(item, min, max)
+}
+
+/// exactly `count` `item`s.
+pub fn exactly<S: RewindSequence, P: Parser<S>>(item: P, count: usize) -> Many<S, P> {
+    Many::new(item, count, count)
+}
+
+#[derive(Debug)]
+pub enum ManyError<E> {
+    /// `item` failed with an error which should terminate.
+    Item(E),
+    /// `item` failed after `count` items, fewer than the minimum.
+    TooFew { count: usize, error: E },
+}
+
+impl<E: ParseError> ParseError for ManyError<E> {
+    fn should_terminate(&self) -> bool {
+        match self {
+            ManyError::Item(e) => e.should_terminate(),
+            ManyError::TooFew { error, .. } => error.should_terminate(),
+        }
+    }
+}
+
+/// collection which only counts the items, for skipping without allocation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Count(pub usize);
+
+impl<T> Extend<T> for Count {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0 += iter.into_iter().count();
+    }
+}
+
+/// as a `Parser`, collect the items into `C`. as an `IterativeParser`, yield each item.
+#[derive(Debug)]
+pub struct Many<S: RewindSequence, P: Parser<S>, C = Vec<<P as ParserOnce<S>>::Output>> {
+    item: P,
+    min: usize,
+    max: usize,
+    marker: PhantomData<(S, C)>,
+}
+
+impl<S: RewindSequence, P: Parser<S>, C> Many<S, P, C> {
+    fn new(item: P, min: usize, max: usize) -> Self {
+        Self {
+            item,
+            min,
+            max,
+            marker: PhantomData,
+        }
+    }
+
+    /// collect the items into `D` instead of `C`.
+    pub fn with_collection<D: Extend<P::Output> + Default>(self) -> Many<S, P, D> {
+        Many::new(self.item, self.min, self.max)
+    }
+
+    /// discard the items and return their count.
+    pub fn skip(self) -> Many<S, P, Count> {
+        self.with_collection()
+    }
+}
+
+impl<S, P, C> ParserOnce<S> for Many<S, P, C>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    C: Extend<P::Output> + Default,
+{
+    type Output = C;
+    type Error = ManyError<P::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<S, P, C> Parser<S> for Many<S, P, C>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    C: Extend<P::Output> + Default,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let mut state = self.parse_iterative();
+        let mut collection = C::default();
+        let mut rest = input;
+
+        loop {
+            match state.parse_next(rest).await {
+                Ok((Some(v), r)) => {
+                    collection.extend(std::iter::once(v));
+                    rest = r;
+                }
+                Ok((None, r)) => return done(collection, r),
+                Err((e, r)) => return fail(e, r),
+            }
+        }
+    }
+}
+
+impl<S: RewindSequence, P: Parser<S>, C> IterativeParserOnce<S> for Many<S, P, C> {
+    type Output = P::Output;
+    type Error = ManyError<P::Error>;
+    type StateOnce = IterationState<S, P>;
+
+    fn parse_iterative_once(self) -> Self::StateOnce {
+        IterationState::new(self.item, self.min, self.max)
+    }
+}
+
+impl<S: RewindSequence, P: Parser<S>, C> IterativeParser<S> for Many<S, P, C> {
+    type State<'a>
+        = IterationState<S, Ref<'a, S, P>>
+    where
+        Self: 'a;
+
+    fn parse_iterative(&self) -> Self::State<'_> {
+        IterationState::new(Ref::new(&self.item), self.min, self.max)
+    }
+}
+
+#[derive(Debug)]
+pub struct IterationState<S: Sequence, P: Parser<S>> {
+    item: P,
+    min: usize,
+    max: usize,
+    count: usize,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, P: Parser<S>> IterationState<S, P> {
+    fn new(item: P, min: usize, max: usize) -> Self {
+        Self {
+            item,
+            min,
+            max,
+            count: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: RewindSequence, P: Parser<S>> IterativeParserState<S> for IterationState<S, P> {
+    type Output = P::Output;
+    type Error = ManyError<P::Error>;
+
+    async fn parse_next(&mut self, input: S) -> ParseResult<S, Option<Self::Output>, Self::Error> {
+        if self.count >= self.max {
+            return done(None, input);
+        }
+
+        let anchor = input.anchor();
+        match self.item.parse(input).await {
+            Ok((v, r)) => {
+                self.count += 1;
+                done(Some(v), r)
+            }
+            Err((e, r)) if e.should_terminate() => fail(ManyError::Item(e), r),
+            Err((error, r)) if self.count < self.min => {
+                let count = self.count;
+                fail(ManyError::TooFew { count, error }, r)
+            }
+            Err((_, r)) => done(None, r.rewind(anchor).await),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::{digit, the_char};
+
+    #[test]
+    fn repeat_within_bounds() {
+        pollster::block_on(async {
+            let (items, rest) = many_m_n(digit(), 2, 3).parse("1234").await.ok().unwrap();
+            assert_eq!(items, ['1', '2', '3']);
+            assert_eq!(rest, "4");
+
+            let Err((ManyError::TooFew { count: 1, .. }, _)) =
+                many_m_n(digit(), 2, 3).parse("1a").await
+            else {
+                panic!()
+            };
+
+            assert!(many1(digit()).parse("a").await.is_err());
+            assert!(exactly(digit(), 2).parse("1").await.is_err());
+
+            let (digits, rest) = many(digit())
+                .with_collection::<String>()
+                .parse("42!")
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!((digits.as_str(), rest), ("42", "!"));
+
+            let (count, rest) = many(the_char(' ')).skip().parse("   x").await.ok().unwrap();
+            assert_eq!((count, rest), (Count(3), "x"));
+        })
+    }
+}
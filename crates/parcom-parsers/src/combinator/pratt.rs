@@ -0,0 +1,494 @@
+use parcom_core::{ParseError, Parser, ParserOnce, ParserResult, RewindSequence};
+use parcom_internals::ShortVec;
+use parcom_util::{done, fail};
+use std::marker::PhantomData;
+
+pub trait PrefixOperator {
+    /// operators in the operand which have lower precedence than this are not included.
+    fn precedence(&self) -> usize;
+}
+
+pub trait PostfixOperator {
+    fn precedence(&self) -> usize;
+}
+
+pub enum Associativity {
+    Left,
+    Right,
+    /// operators of the same precedence cannot be chained.
+    None,
+}
+
+pub trait InfixOperator {
+    fn precedence(&self) -> usize;
+    fn associativity(&self) -> Associativity;
+}
+
+#[derive(Debug)]
+pub enum PrattError<ETerm, EPrefix, EPostfix, EInfix> {
+    Term(ETerm),
+    Prefix(EPrefix),
+    Postfix(EPostfix),
+    Infix(EInfix),
+    /// non-associative operators of the same precedence were chained.
+    NonAssociative,
+}
+
+impl<ETerm, EPrefix, EPostfix, EInfix> ParseError for PrattError<ETerm, EPrefix, EPostfix, EInfix>
+where
+    ETerm: ParseError,
+    EPrefix: ParseError,
+    EPostfix: ParseError,
+    EInfix: ParseError,
+{
+    fn should_terminate(&self) -> bool {
+        match self {
+            PrattError::Term(e) => e.should_terminate(),
+            PrattError::Prefix(e) => e.should_terminate(),
+            PrattError::Postfix(e) => e.should_terminate(),
+            PrattError::Infix(e) => e.should_terminate(),
+            PrattError::NonAssociative => false,
+        }
+    }
+}
+
+/// expression parser with prefix, postfix and infix operators.
+///
+/// operators with a nested operand, e.g. `a ? b : c` or `a[b]`, are parsed by the operator parser
+/// itself, which holds the inner expression as in `? b :` or `[b]`.
+/// e.g. a ternary operator is an infix operator parsed by `delimited(the_char('?'), expr, the_char(':'))`
+/// with right associativity.
+// https://matklad.github.io/2020/04/13/simple-but-powerful-pratt-parsing.html
+#[derive(Debug)]
+pub struct PrattParser<S, PTerm, PPrefix, PPostfix, PInfix, Expr>
+where
+    S: RewindSequence,
+    PTerm: Parser<S>,
+    PPrefix: Parser<S>,
+    PPostfix: Parser<S>,
+    PInfix: Parser<S>,
+    PPrefix::Output: PrefixOperator,
+    PPostfix::Output: PostfixOperator,
+    PInfix::Output: InfixOperator,
+    Expr: From<PTerm::Output>
+        + From<(PPrefix::Output, Expr)>
+        + From<(Expr, PPostfix::Output)>
+        + From<(Expr, PInfix::Output, Expr)>,
+{
+    parser_term: PTerm,
+    parser_prefix: PPrefix,
+    parser_postfix: PPostfix,
+    parser_infix: PInfix,
+    marker: PhantomData<(S, Expr)>,
+}
+
+impl<S, PTerm, PPrefix, PPostfix, PInfix, Expr>
+    PrattParser<S, PTerm, PPrefix, PPostfix, PInfix, Expr>
+where
+    S: RewindSequence,
+    PTerm: Parser<S>,
+    PPrefix: Parser<S>,
+    PPostfix: Parser<S>,
+    PInfix: Parser<S>,
+    PPrefix::Output: PrefixOperator,
+    PPostfix::Output: PostfixOperator,
+    PInfix::Output: InfixOperator,
+    Expr: From<PTerm::Output>
+        + From<(PPrefix::Output, Expr)>
+        + From<(Expr, PPostfix::Output)>
+        + From<(Expr, PInfix::Output, Expr)>,
+{
+    pub fn new(
+        parser_term: PTerm,
+        parser_prefix: PPrefix,
+        parser_postfix: PPostfix,
+        parser_infix: PInfix,
+    ) -> Self {
+        Self {
+            parser_term,
+            parser_prefix,
+            parser_postfix,
+            parser_infix,
+            marker: PhantomData,
+        }
+    }
+}
+
+enum Frame<S: RewindSequence, Pre, In, Expr> {
+    Prefix {
+        op: Pre,
+        min: usize,
+    },
+    Infix {
+        lhs: Expr,
+        op: In,
+        min: usize,
+        /// precedence of the operator if it is non-associative.
+        non_associative: Option<usize>,
+        anchor: S::Anchor,
+    },
+}
+
+impl<S: RewindSequence, Pre, In, Expr> Frame<S, Pre, In, Expr> {
+    fn min(&self) -> usize {
+        match self {
+            Frame::Prefix { min, .. } => *min,
+            Frame::Infix { min, .. } => *min,
+        }
+    }
+}
+
+impl<S, PTerm, PPrefix, PPostfix, PInfix, Expr> ParserOnce<S>
+    for PrattParser<S, PTerm, PPrefix, PPostfix, PInfix, Expr>
+where
+    S: RewindSequence,
+    PTerm: Parser<S>,
+    PPrefix: Parser<S>,
+    PPostfix: Parser<S>,
+    PInfix: Parser<S>,
+    PPrefix::Output: PrefixOperator,
+    PPostfix::Output: PostfixOperator,
+    PInfix::Output: InfixOperator,
+    Expr: From<PTerm::Output>
+        + From<(PPrefix::Output, Expr)>
+        + From<(Expr, PPostfix::Output)>
+        + From<(Expr, PInfix::Output, Expr)>,
+{
+    type Output = Expr;
+    type Error = PrattError<PTerm::Error, PPrefix::Error, PPostfix::Error, PInfix::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<S, PTerm, PPrefix, PPostfix, PInfix, Expr> Parser<S>
+    for PrattParser<S, PTerm, PPrefix, PPostfix, PInfix, Expr>
+where
+    S: RewindSequence,
+    PTerm: Parser<S>,
+    PPrefix: Parser<S>,
+    PPostfix: Parser<S>,
+    PInfix: Parser<S>,
+    PPrefix::Output: PrefixOperator,
+    PPostfix::Output: PostfixOperator,
+    PInfix::Output: InfixOperator,
+    Expr: From<PTerm::Output>
+        + From<(PPrefix::Output, Expr)>
+        + From<(Expr, PPostfix::Output)>
+        + From<(Expr, PInfix::Output, Expr)>,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        // 被演算子を待っている演算子．下から順に min は単調増加する．
+        let mut stack = ShortVec::<Frame<S, PPrefix::Output, PInfix::Output, Expr>, 4>::new();
+        let mut rest = input;
+
+        'operand: loop {
+            let anchor = rest.anchor();
+            match self.parser_prefix.parse(rest).await {
+                Ok((op, r)) => {
+                    let min = op.precedence();
+                    stack.push(Frame::Prefix { op, min });
+                    rest = r;
+                    continue;
+                }
+                Err((e, r)) if e.should_terminate() => return fail(PrattError::Prefix(e), r),
+                Err((_, r)) => rest = r.rewind(anchor).await,
+            }
+
+            let mut lhs = match self.parser_term.parse(rest).await {
+                Ok((t, r)) => {
+                    rest = r;
+                    Expr::from(t)
+                }
+                Err((e, r)) if e.should_terminate() => return fail(PrattError::Term(e), r),
+                Err((e, r)) => {
+                    // 前置演算子を捨て，直前の二項演算子の手前まで戻す．
+                    let (lhs, anchor) = loop {
+                        match stack.pop() {
+                            Some(Frame::Prefix { .. }) => continue,
+                            Some(Frame::Infix { lhs, anchor, .. }) => break (lhs, anchor),
+                            None => return fail(PrattError::Term(e), r),
+                        }
+                    };
+                    let rest = r.rewind(anchor).await;
+                    return done(reduce_all(&mut stack, lhs), rest);
+                }
+            };
+            let mut non_associative = None;
+
+            loop {
+                let anchor = rest.anchor();
+                match self.parser_postfix.parse(rest).await {
+                    Ok((op, r)) => {
+                        let precedence = op.precedence();
+                        lhs = reduce_while(&mut stack, lhs, &mut non_associative, precedence);
+                        lhs = Expr::from((lhs, op));
+                        non_associative = None;
+                        rest = r;
+                        continue;
+                    }
+                    Err((e, r)) if e.should_terminate() => return fail(PrattError::Postfix(e), r),
+                    Err((_, r)) => rest = r.rewind(anchor).await,
+                }
+
+                let anchor = rest.anchor();
+                match self.parser_infix.parse(rest).await {
+                    Ok((op, r)) => {
+                        let precedence = op.precedence();
+                        lhs = reduce_while(&mut stack, lhs, &mut non_associative, precedence);
+                        if non_associative == Some(precedence) {
+                            return fail(PrattError::NonAssociative, r.rewind(anchor).await);
+                        }
+
+                        let (min, non_associative) = match op.associativity() {
+                            Associativity::Left => (precedence + 1, None),
+                            Associativity::Right => (precedence, None),
+                            Associativity::None => (precedence + 1, Some(precedence)),
+                        };
+                        stack.push(Frame::Infix {
+                            lhs,
+                            op,
+                            min,
+                            non_associative,
+                            anchor,
+                        });
+                        rest = r;
+                        continue 'operand;
+                    }
+                    Err((e, r)) if e.should_terminate() => return fail(PrattError::Infix(e), r),
+                    Err((_, r)) => rest = r.rewind(anchor).await,
+                }
+
+                return done(reduce_all(&mut stack, lhs), rest);
+            }
+        }
+    }
+}
+
+/// apply the operators on the stack which bind tighter than `precedence` to `lhs`.
+fn reduce_while<S, Pre, In, Expr>(
+    stack: &mut ShortVec<Frame<S, Pre, In, Expr>, 4>,
+    mut lhs: Expr,
+    non_associative: &mut Option<usize>,
+    precedence: usize,
+) -> Expr
+where
+    S: RewindSequence,
+    Expr: From<(Pre, Expr)> + From<(Expr, In, Expr)>,
+{
+    while stack
+        .as_slice()
+        .last()
+        .is_some_and(|f| precedence < f.min())
+    {
+        let Some(frame) = stack.pop() else {
+            unreachable!()
+        };
+        (lhs, *non_associative) = reduce(frame, lhs);
+    }
+
+    lhs
+}
+
+fn reduce_all<S, Pre, In, Expr>(
+    stack: &mut ShortVec<Frame<S, Pre, In, Expr>, 4>,
+    mut lhs: Expr,
+) -> Expr
+where
+    S: RewindSequence,
+    Expr: From<(Pre, Expr)> + From<(Expr, In, Expr)>,
+{
+    while let Some(frame) = stack.pop() {
+        (lhs, _) = reduce(frame, lhs);
+    }
+
+    lhs
+}
+
+fn reduce<S, Pre, In, Expr>(frame: Frame<S, Pre, In, Expr>, operand: Expr) -> (Expr, Option<usize>)
+where
+    S: RewindSequence,
+    Expr: From<(Pre, Expr)> + From<(Expr, In, Expr)>,
+{
+    match frame {
+        Frame::Prefix { op, .. } => (Expr::from((op, operand)), None),
+        Frame::Infix {
+            lhs,
+            op,
+            non_associative,
+            ..
+        } => (Expr::from((lhs, op, operand)), non_associative),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        combinator::{delimited, recursive, Rule},
+        primitive::{any_char, digit, the_char},
+        ParserExtension,
+    };
+    use parcom_util::error::Miss;
+
+    type Error = PrattError<Miss<()>, Miss<()>, Miss<()>, Miss<()>>;
+
+    #[test]
+    fn parse_operators_of_each_kind() {
+        pollster::block_on(async {
+            let expr: Rule<&str, Expr, Error> = recursive(|expr| {
+                let prefix = any_char().and_then(|c| match c {
+                    '-' => Ok(Op::Neg),
+                    '!' => Ok(Op::Not),
+                    _ => Err(Miss(())),
+                });
+                let postfix = delimited(the_char('['), expr.clone(), the_char(']'))
+                    .map(|e| Op::Index(Box::new(e)))
+                    .or(the_char('!').map(|_| Op::Fact))
+                    .map(|e| e.unify())
+                    .map_err(|_| Miss(()));
+                let infix = delimited(the_char('?'), expr, the_char(':'))
+                    .map(|e| Op::Cond(Box::new(e)))
+                    .or(any_char().and_then(|c| match c {
+                        '+' => Ok(Op::Add),
+                        '*' => Ok(Op::Mul),
+                        '^' => Ok(Op::Pow),
+                        '<' => Ok(Op::Lt),
+                        '=' => Ok(Op::Eq),
+                        _ => Err(Miss(())),
+                    }))
+                    .map(|e| e.unify())
+                    .map_err(|_| Miss(()));
+
+                PrattParser::new(digit(), prefix, postfix, infix)
+            });
+
+            let cases = [
+                ("-1+2*3^4^5", "((- 1) + (2 * (3 ^ (4 ^ 5))))", ""),
+                ("-1!", "(- (1 !))", ""),
+                ("!1[2+3]!", "(! ((1 [(2 + 3)]) !))", ""),
+                ("1<2?3:4?5:6", "((1 < 2) ? 3 : (4 ? 5 : 6))", ""),
+                ("1=2+3", "(1 = (2 + 3))", ""),
+                ("1+-@", "1", "+-@"),
+            ];
+            for (input, expected, expected_rest) in cases {
+                let (e, rest) = expr.parse(input).await.ok().unwrap();
+                assert_eq!((e.to_string(), rest), (expected.to_string(), expected_rest));
+            }
+
+            let Err((PrattError::NonAssociative, _)) = expr.parse("1<2=3").await else {
+                panic!()
+            };
+            assert!(expr.parse("-@").await.is_err());
+        })
+    }
+
+    #[derive(Debug)]
+    enum Expr {
+        Term(char),
+        Prefix(Op, Box<Expr>),
+        Postfix(Box<Expr>, Op),
+        Bin(Box<Expr>, Op, Box<Expr>),
+    }
+
+    impl From<char> for Expr {
+        fn from(c: char) -> Self {
+            Expr::Term(c)
+        }
+    }
+
+    impl From<(Op, Expr)> for Expr {
+        fn from((op, e): (Op, Expr)) -> Self {
+            Expr::Prefix(op, Box::new(e))
+        }
+    }
+
+    impl From<(Expr, Op)> for Expr {
+        fn from((e, op): (Expr, Op)) -> Self {
+            Expr::Postfix(Box::new(e), op)
+        }
+    }
+
+    impl From<(Expr, Op, Expr)> for Expr {
+        fn from((lhs, op, rhs): (Expr, Op, Expr)) -> Self {
+            Expr::Bin(Box::new(lhs), op, Box::new(rhs))
+        }
+    }
+
+    impl std::fmt::Display for Expr {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Expr::Term(c) => write!(f, "{}", c),
+                Expr::Prefix(op, e) => write!(f, "({} {})", op, e),
+                Expr::Postfix(e, op) => write!(f, "({} {})", e, op),
+                Expr::Bin(lhs, op, rhs) => write!(f, "({} {} {})", lhs, op, rhs),
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    enum Op {
+        Neg,
+        Not,
+        Fact,
+        Index(Box<Expr>),
+        Add,
+        Mul,
+        Pow,
+        Lt,
+        Eq,
+        Cond(Box<Expr>),
+    }
+
+    impl std::fmt::Display for Op {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Op::Neg => write!(f, "-"),
+                Op::Not | Op::Fact => write!(f, "!"),
+                Op::Index(e) => write!(f, "[{}]", e),
+                Op::Add => write!(f, "+"),
+                Op::Mul => write!(f, "*"),
+                Op::Pow => write!(f, "^"),
+                Op::Lt => write!(f, "<"),
+                Op::Eq => write!(f, "="),
+                Op::Cond(e) => write!(f, "? {} :", e),
+            }
+        }
+    }
+
+    impl PrefixOperator for Op {
+        fn precedence(&self) -> usize {
+            5
+        }
+    }
+
+    impl PostfixOperator for Op {
+        fn precedence(&self) -> usize {
+            match self {
+                Op::Index(_) => 7,
+                _ => 6,
+            }
+        }
+    }
+
+    impl InfixOperator for Op {
+        fn precedence(&self) -> usize {
+            match self {
+                Op::Cond(_) => 0,
+                Op::Lt | Op::Eq => 1,
+                Op::Add => 2,
+                Op::Mul => 3,
+                _ => 4,
+            }
+        }
+
+        fn associativity(&self) -> Associativity {
+            match self {
+                Op::Cond(_) | Op::Pow => Associativity::Right,
+                Op::Lt | Op::Eq => Associativity::None,
+                _ => Associativity::Left,
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use super::{exactly, ManyError, Ref};
+use parcom_core::{
+    take, ParseError, ParseResult, Parser, ParserOnce, ParserResult, RewindSequence, SegmentStream,
+    Sequence, SequenceSegment, Take,
+};
+use parcom_util::{done, fail};
+use std::marker::PhantomData;
+
+/// read a length with `prefix` and run `body` on exactly that length of the input.
+///
+/// the length is counted in items. `str` sequences are not supported since a length read from the input may split a char.
+pub fn length_prefixed<S, L, P>(prefix: L, body: P) -> LengthPrefixed<S, L, P>
+where
+    S: Sequence<Length = usize>,
+    L: ParserOnce<S>,
+    P: ParserOnce<Take<S>>,
+    Take<S>: Sequence,
+{
+    LengthPrefixed::new(prefix, body)
+}
+
+/// read a count with `prefix` and repeat `item` exactly that many times.
+pub fn count_prefixed<S, L, P>(prefix: L, item: P) -> CountPrefixed<S, L, P>
+where
+    S: RewindSequence,
+    L: ParserOnce<S>,
+    P: Parser<S>,
+{
+    CountPrefixed::new(prefix, item)
+}
+
+#[derive(Debug)]
+pub enum PrefixedError<E0, E1> {
+    Prefix(E0),
+    Body(E1),
+    /// the prefix does not fit in the length type.
+    Overflow,
+    /// the body finished before consuming the whole length.
+    Unconsumed,
+    /// the input ended before the whole length.
+    Truncated,
+}
+
+impl<E0: ParseError, E1: ParseError> ParseError for PrefixedError<E0, E1> {
+    fn should_terminate(&self) -> bool {
+        match self {
+            PrefixedError::Prefix(e) => e.should_terminate(),
+            PrefixedError::Body(e) => e.should_terminate(),
+            PrefixedError::Overflow | PrefixedError::Unconsumed | PrefixedError::Truncated => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LengthPrefixed<S: Sequence, L: ParserOnce<S>, P: ParserOnce<Take<S>>>
+where
+    Take<S>: Sequence,
+{
+    prefix: L,
+    body: P,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, L: ParserOnce<S>, P: ParserOnce<Take<S>>> LengthPrefixed<S, L, P>
+where
+    Take<S>: Sequence,
+{
+    pub fn new(prefix: L, body: P) -> Self {
+        Self {
+            prefix,
+            body,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S, L, P> ParserOnce<S> for LengthPrefixed<S, L, P>
+where
+    S: Sequence<Length = usize>,
+    S::Segment: SequenceSegment<Length = usize>,
+    usize: TryFrom<L::Output>,
+    L: ParserOnce<S>,
+    P: ParserOnce<Take<S>>,
+{
+    type Output = P::Output;
+    type Error = PrefixedError<L::Error, P::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        let (len, rest) = self
+            .prefix
+            .parse_once(input)
+            .await
+            .map_err(|(e, r)| (PrefixedError::Prefix(e), r))?;
+        let Ok(len) = usize::try_from(len) else {
+            return fail(PrefixedError::Overflow, rest);
+        };
+
+        finish(self.body.parse_once(take(rest, len)).await).await
+    }
+}
+
+impl<S, L, P> Parser<S> for LengthPrefixed<S, L, P>
+where
+    S: Sequence<Length = usize>,
+    S::Segment: SequenceSegment<Length = usize>,
+    usize: TryFrom<L::Output>,
+    L: Parser<S>,
+    P: Parser<Take<S>>,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let (len, rest) = self
+            .prefix
+            .parse(input)
+            .await
+            .map_err(|(e, r)| (PrefixedError::Prefix(e), r))?;
+        let Ok(len) = usize::try_from(len) else {
+            return fail(PrefixedError::Overflow, rest);
+        };
+
+        finish(self.body.parse(take(rest, len)).await).await
+    }
+}
+
+async fn finish<S, O, E0, E1>(
+    result: ParseResult<Take<S>, O, E1>,
+) -> ParseResult<S, O, PrefixedError<E0, E1>>
+where
+    S: Sequence<Length = usize>,
+    S::Segment: SequenceSegment<Length = usize>,
+    E0: ParseError,
+    E1: ParseError,
+{
+    match result {
+        Ok((v, mut r)) => {
+            if *r.remain() > 0 {
+                // 残りの長さを読めるかどうかで、入力が足りないのかを区別する。
+                let e = if is_empty(&mut r).await {
+                    PrefixedError::Truncated
+                } else {
+                    PrefixedError::Unconsumed
+                };
+                return fail(e, r.into_inner());
+            }
+
+            done(v, r.into_inner())
+        }
+        // SAFETY: 位置は不明のまま、型だけを戻す。
+        Err((e, r)) => Err((PrefixedError::Body(e), unsafe { r.map(Take::into_inner) })),
+    }
+}
+
+async fn is_empty<S>(input: &mut Take<S>) -> bool
+where
+    S: Sequence<Length = usize>,
+    S::Segment: SequenceSegment<Length = usize>,
+{
+    let mut segments = input.segments();
+    while let Some(segment) = segments.next(0).await {
+        if segment.len() > 0 {
+            return false;
+        }
+    }
+    true
+}
+
+#[derive(Debug)]
+pub struct CountPrefixed<S: Sequence, L: ParserOnce<S>, P: Parser<S>> {
+    prefix: L,
+    item: P,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, L: ParserOnce<S>, P: Parser<S>> CountPrefixed<S, L, P> {
+    pub fn new(prefix: L, item: P) -> Self {
+        Self {
+            prefix,
+            item,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S, L, P> ParserOnce<S> for CountPrefixed<S, L, P>
+where
+    S: RewindSequence,
+    L: ParserOnce<S>,
+    P: Parser<S>,
+    usize: TryFrom<L::Output>,
+{
+    type Output = Vec<P::Output>;
+    type Error = PrefixedError<L::Error, ManyError<P::Error>>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        let (count, rest) = self
+            .prefix
+            .parse_once(input)
+            .await
+            .map_err(|(e, r)| (PrefixedError::Prefix(e), r))?;
+
+        let Ok(count) = usize::try_from(count) else {
+            return fail(PrefixedError::Overflow, rest);
+        };
+
+        exactly(self.item, count)
+            .parse_once(rest)
+            .await
+            .map_err(|(e, r)| (PrefixedError::Body(e), r))
+    }
+}
+
+impl<S, L, P> Parser<S> for CountPrefixed<S, L, P>
+where
+    S: RewindSequence,
+    L: Parser<S>,
+    P: Parser<S>,
+    usize: TryFrom<L::Output>,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let (count, rest) = self
+            .prefix
+            .parse(input)
+            .await
+            .map_err(|(e, r)| (PrefixedError::Prefix(e), r))?;
+
+        let Ok(count) = usize::try_from(count) else {
+            return fail(PrefixedError::Overflow, rest);
+        };
+
+        exactly(Ref::new(&self.item), count)
+            .parse(rest)
+            .await
+            .map_err(|(e, r)| (PrefixedError::Body(e), r))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        binary::big_endian,
+        primitive::{satisfy_item, the_item},
+        ParserExtension,
+    };
+
+    #[test]
+    fn parse_exact_length_and_count() {
+        pollster::block_on(async {
+            let input = [2u8, 0, 0, 7].as_slice();
+            let parser = length_prefixed(big_endian::<u8>(), the_item(0u8).repeat());
+            let ((items, _), rest) = parser.parse(input).await.ok().unwrap();
+            assert_eq!(items.len(), 2);
+            assert_eq!(rest, [7]);
+
+            let parser = length_prefixed(big_endian::<u8>(), the_item(0u8));
+            let Err((PrefixedError::Unconsumed, _)) = parser.parse(input).await else {
+                panic!()
+            };
+
+            let parser = length_prefixed(big_endian::<u8>(), the_item(0u8).repeat());
+            let Err((PrefixedError::Truncated, _)) = parser.parse([3u8, 0, 0].as_slice()).await
+            else {
+                panic!()
+            };
+
+            let parser = length_prefixed(big_endian::<u8>(), the_item(0u8).repeat());
+            let Err((PrefixedError::Unconsumed, _)) = parser.parse([2u8, 0, 1].as_slice()).await
+            else {
+                panic!()
+            };
+
+            let parser = count_prefixed(big_endian::<u16>(), satisfy_item(|b: &u8| *b < 0x80));
+            let input = [0u8, 3, 1, 2, 3, 4].as_slice();
+            let (items, rest) = parser.parse(input).await.ok().unwrap();
+            assert_eq!(items, [1, 2, 3]);
+            assert_eq!(rest, [4]);
+
+            let Err((PrefixedError::Body(_), _)) = parser.parse([0u8, 2, 1].as_slice()).await
+            else {
+                panic!()
+            };
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use parcom_core::{Parser, ParserOnce, ParserResult, RecognizeSequence, Sequence};
+use parcom_util::done;
+use std::marker::PhantomData;
+
+/// return the part of the input consumed by the parser instead of its output.
+///
+/// `S` is the recorder of the input on which the parser runs.
+#[derive(Debug)]
+pub struct Recognize<S: Sequence, P: ParserOnce<S>> {
+    parser: P,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, P: ParserOnce<S>> Recognize<S, P> {
+    pub fn new(parser: P) -> Self {
+        Self {
+            parser,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, P> ParserOnce<T> for Recognize<T::Recorder, P>
+where
+    T: RecognizeSequence,
+    P: ParserOnce<T::Recorder>,
+{
+    type Output = T::Span;
+    type Error = P::Error;
+
+    async fn parse_once(self, input: T) -> ParserResult<T, Self> {
+        let (recorder, mark) = input.record();
+        match self.parser.parse_once(recorder).await {
+            Ok((_, r)) => {
+                let (span, r) = T::recognize(r, mark);
+                done(span, r)
+            }
+            // SAFETY: 位置は不明のまま、型だけを戻す。
+            Err((e, r)) => Err((e, unsafe { r.map(|r| T::recognize(r, mark).1) })),
+        }
+    }
+}
+
+impl<T, P> Parser<T> for Recognize<T::Recorder, P>
+where
+    T: RecognizeSequence,
+    P: Parser<T::Recorder>,
+{
+    async fn parse(&self, input: T) -> ParserResult<T, Self> {
+        let (recorder, mark) = input.record();
+        match self.parser.parse(recorder).await {
+            Ok((_, r)) => {
+                let (span, r) = T::recognize(r, mark);
+                done(span, r)
+            }
+            // SAFETY: 同上。
+            Err((e, r)) => Err((e, unsafe { r.map(|r| T::recognize(r, mark).1) })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        primitive::{the_char, the_item},
+        ParserExtension,
+    };
+    use parcom_core::Parser;
+
+    #[test]
+    fn recognize_borrows_consumed_input() {
+        pollster::block_on(async {
+            let parser = the_char('a').join(the_char('b')).recognize();
+            let (span, rest) = parser.parse("abc").await.ok().unwrap();
+            assert_eq!(span, "ab");
+            assert_eq!(rest, "c");
+
+            let parser = the_item(0u8).repeat().recognize();
+            let (span, rest) = parser.parse([0u8, 0, 1].as_slice()).await.ok().unwrap();
+            assert_eq!(span, [0, 0]);
+            assert_eq!(rest, [1]);
+        })
+    }
+}
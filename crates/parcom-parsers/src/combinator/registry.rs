@@ -0,0 +1,103 @@
+use super::{Rule, WeakRule};
+use parcom_core::{ParseError, Parser, Sequence};
+use std::collections::HashMap;
+
+/// table of `Rule`s keyed by name, for grammars built at runtime.
+///
+/// the registry owns the rules. parsers refer to each other through `WeakRule`s
+/// returned by `reference`, so the rules may be defined in any order.
+pub struct Registry<'p, S, O, E> {
+    rules: HashMap<String, Rule<'p, S, O, E>>,
+}
+
+impl<'p, S: Sequence, O, E: ParseError> Registry<'p, S, O, E> {
+    pub fn new() -> Self {
+        Self {
+            rules: HashMap::new(),
+        }
+    }
+
+    /// panics if the rule is already defined.
+    pub fn define<P: 'p + Parser<S, Output = O, Error = E>>(
+        &mut self,
+        name: impl Into<String>,
+        parser: P,
+    ) {
+        self.rules.entry(name.into()).or_default().define(parser);
+    }
+
+    /// return a reference to the rule, declaring it if it does not exist yet.
+    pub fn reference(&mut self, name: impl Into<String>) -> WeakRule<'p, S, O, E> {
+        self.rules.entry(name.into()).or_default().weak()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Rule<'p, S, O, E>> {
+        self.rules.get(name)
+    }
+
+    /// return the names of rules which are referenced but not defined.
+    pub fn undefined(&self) -> Vec<&str> {
+        self.rules
+            .iter()
+            .filter(|(_, rule)| !rule.is_defined())
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+impl<'p, S: Sequence, O, E: ParseError> Default for Registry<'p, S, O, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'p, S, O, E> std::fmt::Debug for Registry<'p, S, O, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.rules.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        combinator::{delimited, many},
+        primitive::{digit, the_char},
+        ParserExtension,
+    };
+    use parcom_util::error::Miss;
+
+    #[test]
+    fn build_rules_from_names() {
+        pollster::block_on(async {
+            // sum = "(" term* ")", term = digit / sum
+            let table = [("sum", "term"), ("term", "sum")];
+
+            let mut registry: Registry<&str, u32, Miss<()>> = Registry::new();
+            for (name, other) in table {
+                let other = registry.reference(other);
+                let parser = match name {
+                    "sum" => delimited(the_char('('), many(other), the_char(')'))
+                        .map(|v: Vec<u32>| v.into_iter().sum())
+                        .map_err(|_| Miss(()))
+                        .into_dyn(),
+                    _ => digit()
+                        .map(|c| c.to_digit(10).unwrap())
+                        .or(other)
+                        .map(|e| e.unify())
+                        .map_err(|_| Miss(()))
+                        .into_dyn(),
+                };
+                registry.define(name, parser);
+            }
+            assert_eq!(registry.undefined().len(), 0);
+
+            let sum = registry.get("sum").unwrap();
+            let (v, rest) = sum.parse("(1(23)4)!").await.ok().unwrap();
+            assert_eq!((v, rest), (10, "!"));
+
+            registry.reference("missing");
+            assert_eq!(registry.undefined(), ["missing"]);
+        })
+    }
+}
@@ -0,0 +1,184 @@
+use super::DynParser;
+use parcom_core::{ParseError, Parser, ParserOnce, ParserResult, Sequence};
+use std::{
+    cell::OnceCell,
+    rc::{Rc, Weak},
+};
+
+/// build a parser which refers to itself through the `WeakRule` passed to `f`.
+pub fn recursive<'p, S, O, E, P, F>(f: F) -> Rule<'p, S, O, E>
+where
+    S: Sequence,
+    E: ParseError,
+    P: 'p + Parser<S, Output = O, Error = E>,
+    F: FnOnce(WeakRule<'p, S, O, E>) -> P,
+{
+    let rule = Rule::new();
+    rule.define(f(rule.weak()));
+    rule
+}
+
+type Slot<'p, S, O, E> = OnceCell<DynParser<'p, S, O, E>>;
+
+/// handle to a parser which can be declared before it is defined.
+///
+/// rules which refer to each other should hold `WeakRule`s for back references,
+/// since strong handles in a cycle are never freed.
+pub struct Rule<'p, S, O, E> {
+    slot: Rc<Slot<'p, S, O, E>>,
+}
+
+impl<'p, S: Sequence, O, E: ParseError> Rule<'p, S, O, E> {
+    pub fn new() -> Self {
+        Self {
+            slot: Rc::new(OnceCell::new()),
+        }
+    }
+
+    /// panics if the rule is already defined.
+    pub fn define<P: 'p + Parser<S, Output = O, Error = E>>(&self, parser: P) {
+        if self.slot.set(DynParser::new(parser)).is_err() {
+            panic!("the rule is already defined.");
+        }
+    }
+
+    pub fn is_defined(&self) -> bool {
+        self.slot.get().is_some()
+    }
+
+    pub fn weak(&self) -> WeakRule<'p, S, O, E> {
+        WeakRule {
+            slot: Rc::downgrade(&self.slot),
+        }
+    }
+}
+
+impl<'p, S: Sequence, O, E: ParseError> Default for Rule<'p, S, O, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'p, S, O, E> Clone for Rule<'p, S, O, E> {
+    fn clone(&self) -> Self {
+        Self {
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+impl<'p, S, O, E> std::fmt::Debug for Rule<'p, S, O, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Rule")
+            .field("defined", &self.slot.get().is_some())
+            .finish()
+    }
+}
+
+impl<'p, S: Sequence, O, E: ParseError> ParserOnce<S> for Rule<'p, S, O, E> {
+    type Output = O;
+    type Error = E;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<'p, S: Sequence, O, E: ParseError> Parser<S> for Rule<'p, S, O, E> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let parser = self
+            .slot
+            .get()
+            .expect("the rule was used before it was defined.");
+        parser.parse(input).await
+    }
+}
+
+/// handle to a `Rule` which does not keep it alive.
+pub struct WeakRule<'p, S, O, E> {
+    slot: Weak<Slot<'p, S, O, E>>,
+}
+
+impl<'p, S, O, E> Clone for WeakRule<'p, S, O, E> {
+    fn clone(&self) -> Self {
+        Self {
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+impl<'p, S, O, E> std::fmt::Debug for WeakRule<'p, S, O, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakRule").finish_non_exhaustive()
+    }
+}
+
+impl<'p, S: Sequence, O, E: ParseError> ParserOnce<S> for WeakRule<'p, S, O, E> {
+    type Output = O;
+    type Error = E;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<'p, S: Sequence, O, E: ParseError> Parser<S> for WeakRule<'p, S, O, E> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let slot = self.slot.upgrade().expect("the rule was dropped.");
+        let parser = slot
+            .get()
+            .expect("the rule was used before it was defined.");
+        parser.parse(input).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        combinator::delimited,
+        primitive::{digit, the_char},
+        ParserExtension,
+    };
+    use parcom_util::error::Miss;
+
+    #[test]
+    fn parse_nested_parentheses() {
+        pollster::block_on(async {
+            // nest = digit / "(" nest ")"
+            let nest = recursive(|nest| {
+                digit()
+                    .map(|_| 0usize)
+                    .or(delimited(the_char('('), nest, the_char(')')).map(|d: usize| d + 1))
+                    .map(|e| e.unify())
+                    .map_err(|_| Miss(()))
+            });
+
+            let (depth, rest) = nest.parse("((1))!").await.ok().unwrap();
+            assert_eq!((depth, rest), (2, "!"));
+            assert!(nest.parse("((1)").await.is_err());
+
+            // list = "[" item* "]", item = digit / list
+            let list: Rule<&str, usize, Miss<()>> = Rule::new();
+            let item: Rule<&str, usize, Miss<()>> = Rule::new();
+            item.define(
+                digit()
+                    .map(|_| 1usize)
+                    .or(list.weak())
+                    .map(|e| e.unify())
+                    .map_err(|_| Miss(())),
+            );
+            list.define(
+                delimited(
+                    the_char('['),
+                    item.clone().repeat().map(|(v, _)| v.into_iter().sum()),
+                    the_char(']'),
+                )
+                .map_err(|_| Miss(())),
+            );
+
+            let (count, rest) = list.parse("[1[23]4]").await.ok().unwrap();
+            assert_eq!((count, rest), (4, ""));
+        })
+    }
+}
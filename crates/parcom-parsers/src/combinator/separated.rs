@@ -0,0 +1,311 @@
+use parcom_core::{
+    IterativeParser, IterativeParserOnce, IterativeParserState, ParseError, ParseResult, Parser,
+    ParserOnce, ParserResult, RewindSequence, Sequence, UnknownLocation,
+};
+use parcom_util::{done, fail, Either};
+use std::marker::PhantomData;
+
+use super::Ref;
+
+/// zero or more `item`s separated by `separator`.
+pub fn separated_by<S, P, Q>(item: P, separator: Q) -> SeparatedBy<S, P, Q>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+{
+    SeparatedBy::new(item, separator, 0, Trailing::Forbid)
+}
+
+/// one or more `item`s separated by `separator`.
+pub fn separated_by1<S, P, Q>(item: P, separator: Q) -> SeparatedBy<S, P, Q>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+{
+    SeparatedBy::new(item, separator, 1, Trailing::Forbid)
+}
+
+/// zero or more `item`s each followed by `terminator`.
+pub fn terminated_by<S, P, Q>(item: P, terminator: Q) -> SeparatedBy<S, P, Q>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+{
+    SeparatedBy::new(item, terminator, 0, Trailing::Require)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trailing {
+    Forbid,
+    Allow,
+    Require,
+}
+
+/// as a `Parser`, collect the items into `C`. as an `IterativeParser`, yield each item.
+#[derive(Debug)]
+pub struct SeparatedBy<S, P, Q, C = Vec<<P as ParserOnce<S>>::Output>>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+{
+    item: P,
+    separator: Q,
+    min: usize,
+    trailing: Trailing,
+    marker: PhantomData<(S, C)>,
+}
+
+impl<S, P, Q, C> SeparatedBy<S, P, Q, C>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+{
+    fn new(item: P, separator: Q, min: usize, trailing: Trailing) -> Self {
+        Self {
+            item,
+            separator,
+            min,
+            trailing,
+            marker: PhantomData,
+        }
+    }
+
+    /// accept a separator after the last item. no effect on `terminated_by`.
+    pub fn allow_trailing(mut self) -> Self {
+        if self.trailing == Trailing::Forbid {
+            self.trailing = Trailing::Allow;
+        }
+        self
+    }
+
+    /// collect the items into `D` instead of `C`.
+    pub fn with_collection<D: Extend<P::Output> + Default>(self) -> SeparatedBy<S, P, Q, D> {
+        SeparatedBy::new(self.item, self.separator, self.min, self.trailing)
+    }
+}
+
+impl<S, P, Q, C> ParserOnce<S> for SeparatedBy<S, P, Q, C>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+    C: Extend<P::Output> + Default,
+{
+    type Output = C;
+    type Error = Either<P::Error, Q::Error>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<S, P, Q, C> Parser<S> for SeparatedBy<S, P, Q, C>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+    C: Extend<P::Output> + Default,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let mut state = self.parse_iterative();
+        let mut collection = C::default();
+        let mut rest = input;
+
+        loop {
+            match state.parse_next(rest).await {
+                Ok((Some(v), r)) => {
+                    collection.extend(std::iter::once(v));
+                    rest = r;
+                }
+                Ok((None, r)) => return done(collection, r),
+                Err((e, r)) => return fail(e, r),
+            }
+        }
+    }
+}
+
+impl<S, P, Q, C> IterativeParserOnce<S> for SeparatedBy<S, P, Q, C>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+{
+    type Output = P::Output;
+    type Error = Either<P::Error, Q::Error>;
+    type StateOnce = IterationState<S, P, Q>;
+
+    fn parse_iterative_once(self) -> Self::StateOnce {
+        IterationState::new(self.item, self.separator, self.min, self.trailing)
+    }
+}
+
+impl<S, P, Q, C> IterativeParser<S> for SeparatedBy<S, P, Q, C>
+where
+    S: RewindSequence,
+    P: Parser<S>,
+    Q: Parser<S>,
+{
+    type State<'a>
+        = IterationState<S, Ref<'a, S, P>, Ref<'a, S, Q>>
+    where
+        Self: 'a;
+
+    fn parse_iterative(&self) -> Self::State<'_> {
+        IterationState::new(
+            Ref::new(&self.item),
+            Ref::new(&self.separator),
+            self.min,
+            self.trailing,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct IterationState<S: Sequence, P: Parser<S>, Q: Parser<S>> {
+    item: P,
+    separator: Q,
+    min: usize,
+    trailing: Trailing,
+    count: usize,
+    /// `true` if no more items can follow.
+    finished: bool,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, P: Parser<S>, Q: Parser<S>> IterationState<S, P, Q> {
+    fn new(item: P, separator: Q, min: usize, trailing: Trailing) -> Self {
+        Self {
+            item,
+            separator,
+            min,
+            trailing,
+            count: 0,
+            finished: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// end the iteration at `anchor`, or fail with `err` if too few items were parsed.
+    async fn end(
+        &mut self,
+        err: Either<P::Error, Q::Error>,
+        rest: UnknownLocation<S>,
+        anchor: S::Anchor,
+    ) -> ParseResult<S, Option<P::Output>, Either<P::Error, Q::Error>>
+    where
+        S: RewindSequence,
+    {
+        self.finished = true;
+        if self.count < self.min {
+            return fail(err, rest);
+        }
+
+        done(None, rest.rewind(anchor).await)
+    }
+}
+
+impl<S: RewindSequence, P: Parser<S>, Q: Parser<S>> IterativeParserState<S>
+    for IterationState<S, P, Q>
+{
+    type Output = P::Output;
+    type Error = Either<P::Error, Q::Error>;
+
+    async fn parse_next(&mut self, input: S) -> ParseResult<S, Option<Self::Output>, Self::Error> {
+        if self.finished {
+            return done(None, input);
+        }
+
+        let anchor = input.anchor();
+        let mut rest = input;
+
+        // 末尾の区切りを許さない場合、区切りは次の要素とあわせて読む。
+        if self.trailing == Trailing::Forbid && self.count > 0 {
+            rest = match self.separator.parse(rest).await {
+                Ok((_, r)) => r,
+                Err((e, r)) if e.should_terminate() => return fail(Either::Last(e), r),
+                Err((e, r)) => return self.end(Either::Last(e), r, anchor).await,
+            };
+        }
+
+        let (v, rest) = match self.item.parse(rest).await {
+            Ok((v, r)) => (v, r),
+            Err((e, r)) if e.should_terminate() => return fail(Either::First(e), r),
+            Err((e, r)) => return self.end(Either::First(e), r, anchor).await,
+        };
+
+        let rest = match self.trailing {
+            Trailing::Forbid => rest,
+            Trailing::Allow => {
+                // 末尾の区切りを許す場合、区切りは要素の直後に読んでおく。
+                let after_item = rest.anchor();
+                match self.separator.parse(rest).await {
+                    Ok((_, r)) => r,
+                    Err((e, r)) if e.should_terminate() => return fail(Either::Last(e), r),
+                    Err((_, r)) => {
+                        self.finished = true;
+                        r.rewind(after_item).await
+                    }
+                }
+            }
+            Trailing::Require => match self.separator.parse(rest).await {
+                Ok((_, r)) => r,
+                Err((e, r)) if e.should_terminate() => return fail(Either::Last(e), r),
+                Err((e, r)) => return self.end(Either::Last(e), r, anchor).await,
+            },
+        };
+
+        self.count += 1;
+        done(Some(v), rest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        primitive::{digit, the_char},
+        IterativeParserExtension,
+    };
+
+    #[test]
+    fn parse_separated_lists() {
+        pollster::block_on(async {
+            let list = separated_by(digit(), the_char(','));
+            let (items, rest) = list.parse("1,2,3,").await.ok().unwrap();
+            assert_eq!(items, ['1', '2', '3']);
+            assert_eq!(rest, ",");
+
+            let (items, rest) = list.parse("x").await.ok().unwrap();
+            assert!(items.is_empty());
+            assert_eq!(rest, "x");
+
+            let list = separated_by(digit(), the_char(',')).allow_trailing();
+            let (items, rest) = list.parse("1,2,)").await.ok().unwrap();
+            assert_eq!(items, ['1', '2']);
+            assert_eq!(rest, ")");
+
+            let list = separated_by1(digit(), the_char(','));
+            assert!(list.parse("x").await.is_err());
+
+            let list = terminated_by(digit(), the_char(';')).with_collection::<String>();
+            let (items, rest) = list.parse("1;2;3").await.ok().unwrap();
+            assert_eq!(items, "12");
+            assert_eq!(rest, "3");
+
+            let list = separated_by(digit(), the_char(','));
+            let (sum, rest) = list
+                .fold(0, |acc, c| acc + c.to_digit(10).unwrap())
+                .parse("1,2,3")
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(sum, 6);
+            assert_eq!(rest, "");
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use parcom_core::{ParseError, ParseResult, Parser, ParserOnce, ParserResult, Sequence};
+use parcom_util::{done, Either2, Either3, Either4, Either5, Either6, Either7, Either8};
+use std::{future::Future, marker::PhantomData};
+
+/// run each parser of the tuple `parsers` in order and return their outputs as a flat tuple.
+pub fn seq<S: Sequence, T: SeqParsersOnce<S>>(parsers: T) -> Seq<S, T> {
+    Seq::new(parsers)
+}
+
+/// tuple of parsers run by `Seq`.
+pub trait SeqParsersOnce<S: Sequence> {
+    type Output;
+    type Error: ParseError;
+
+    fn parse_seq_once(
+        self,
+        input: S,
+    ) -> impl Future<Output = ParseResult<S, Self::Output, Self::Error>>;
+}
+
+pub trait SeqParsers<S: Sequence>: SeqParsersOnce<S> {
+    fn parse_seq(
+        &self,
+        input: S,
+    ) -> impl Future<Output = ParseResult<S, Self::Output, Self::Error>>;
+}
+
+macro_rules! impl_seq_parsers {
+    ($($either: ident { $($p: ident $i: tt $v: ident $o: ident),* })*) => {
+        $(
+            impl<S: Sequence, $($p: ParserOnce<S>),*> SeqParsersOnce<S> for ($($p,)*) {
+                type Output = ($(<$p as ParserOnce<S>>::Output,)*);
+                type Error = $either<$(<$p as ParserOnce<S>>::Error),*>;
+
+                async fn parse_seq_once(self, input: S) -> ParseResult<S, Self::Output, Self::Error> {
+                    let rest = input;
+                    $(
+                        let ($o, rest) = self
+                            .$i
+                            .parse_once(rest)
+                            .await
+                            .map_err(|(e, r)| ($either::$v(e), r))?;
+                    )*
+
+                    done(($($o,)*), rest)
+                }
+            }
+
+            impl<S: Sequence, $($p: Parser<S>),*> SeqParsers<S> for ($($p,)*) {
+                async fn parse_seq(&self, input: S) -> ParseResult<S, Self::Output, Self::Error> {
+                    let rest = input;
+                    $(
+                        let ($o, rest) = self
+                            .$i
+                            .parse(rest)
+                            .await
+                            .map_err(|(e, r)| ($either::$v(e), r))?;
+                    )*
+
+                    done(($($o,)*), rest)
+                }
+            }
+        )*
+    };
+}
+
+impl_seq_parsers! {
+    Either2 { P0 0 V0 o0, P1 1 V1 o1 }
+    Either3 { P0 0 V0 o0, P1 1 V1 o1, P2 2 V2 o2 }
+    Either4 { P0 0 V0 o0, P1 1 V1 o1, P2 2 V2 o2, P3 3 V3 o3 }
+    Either5 { P0 0 V0 o0, P1 1 V1 o1, P2 2 V2 o2, P3 3 V3 o3, P4 4 V4 o4 }
+    Either6 { P0 0 V0 o0, P1 1 V1 o1, P2 2 V2 o2, P3 3 V3 o3, P4 4 V4 o4, P5 5 V5 o5 }
+    Either7 { P0 0 V0 o0, P1 1 V1 o1, P2 2 V2 o2, P3 3 V3 o3, P4 4 V4 o4, P5 5 V5 o5, P6 6 V6 o6 }
+    Either8 { P0 0 V0 o0, P1 1 V1 o1, P2 2 V2 o2, P3 3 V3 o3, P4 4 V4 o4, P5 5 V5 o5, P6 6 V6 o6, P7 7 V7 o7 }
+}
+
+#[derive(Debug)]
+pub struct Seq<S: Sequence, T: SeqParsersOnce<S>> {
+    parsers: T,
+    marker: PhantomData<S>,
+}
+
+impl<S: Sequence, T: SeqParsersOnce<S>> Seq<S, T> {
+    pub fn new(parsers: T) -> Self {
+        Self {
+            parsers,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: Sequence, T: SeqParsersOnce<S>> ParserOnce<S> for Seq<S, T> {
+    type Output = T::Output;
+    type Error = T::Error;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parsers.parse_seq_once(input).await
+    }
+}
+
+impl<S: Sequence, T: SeqParsers<S>> Parser<S> for Seq<S, T> {
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        self.parsers.parse_seq(input).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::{atom, the_char};
+
+    #[test]
+    fn flat_tuple_output() {
+        pollster::block_on(async {
+            let parser = seq((
+                the_char('('),
+                atom("a"),
+                the_char(','),
+                atom("b"),
+                the_char(')'),
+            ));
+
+            let ((_, _, _, _, _), rest) = parser.parse("(a,b)!").await.ok().unwrap();
+            assert_eq!(rest, "!");
+
+            let Err((Either5::V3(_), _)) = parser.parse("(a,c)").await else {
+                panic!()
+            };
+        })
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{util::Boxed, AndThen, Join, Map, MapErr, Optional, Or, Ref, Repeat, Unify, UnifyErr};
+use crate::{
+    util::Boxed, AndThen, Cut, DynParser, Join, Map, MapErr, Optional, Or, Recognize, Ref, Repeat,
+    Unify, UnifyErr,
+};
 use parcom_core::{ParseError, Parser, ParserOnce, RewindSequence, Sequence};
 use parcom_util::Either;
 
@@ -27,6 +30,23 @@ pub trait ParserExtension<S: Sequence>: ParserOnce<S> {
         Join::new(self, other)
     }
 
+    /// make every error of `self` terminal.
+    fn cut(self) -> Cut<S, Self>
+    where
+        Self: Sized,
+    {
+        Cut::new(self)
+    }
+
+    /// run `rest` after `self`. once `self` succeeds, errors of `rest` are terminal.
+    fn then_cut<P: ParserOnce<S>>(self, rest: P) -> Join<S, Self, Cut<S, P>>
+    where
+        Self: Sized,
+        S: RewindSequence,
+    {
+        Join::new(self, Cut::new(rest))
+    }
+
     fn map<U, F: Fn(Self::Output) -> U>(self, mapping: F) -> Map<S, Self, U, F>
     where
         Self: Sized,
@@ -83,12 +103,28 @@ pub trait ParserExtension<S: Sequence>: ParserOnce<S> {
         AndThen::new(self, map)
     }
 
+    /// the returned parser runs on sequences whose recorder is `S`.
+    fn recognize(self) -> Recognize<S, Self>
+    where
+        Self: Sized,
+    {
+        Recognize::new(self)
+    }
+
     fn boxed(self) -> Boxed<S, Self>
     where
         Self: Sized,
     {
         Boxed::new(self)
     }
+
+    /// erase the type of `self` so that it can be stored with other parsers.
+    fn into_dyn<'p>(self) -> DynParser<'p, S, Self::Output, Self::Error>
+    where
+        Self: 'p + Sized + Parser<S>,
+    {
+        DynParser::new(self)
+    }
 }
 
 impl<S: Sequence, P: Parser<S>> ParserExtension<S> for P {}
@@ -1,7 +1,10 @@
+pub mod binary;
 pub mod combinator;
 pub mod extension;
 pub mod iterative;
+pub mod number;
 pub mod primitive;
+pub mod string;
 pub mod util;
 
 pub use combinator::*;
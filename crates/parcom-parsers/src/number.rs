@@ -0,0 +1,8 @@
+pub mod float;
+pub mod integer;
+
+pub use float::{float, Float, PrimitiveFloat};
+pub use integer::{
+    bin_integer, hex_integer, integer, oct_integer, prefixed_integer, Integer, IntegerError,
+    PrimitiveInt,
+};
@@ -0,0 +1,211 @@
+use parcom_core::{
+    primitive::BytesDelta, Parser, ParserOnce, ParserResult, SegmentStream, Sequence,
+};
+use parcom_util::{done, error::Miss, fail};
+use std::{marker::PhantomData, str::FromStr};
+
+/// decimal float such as `-1.5e3`, `.5`, `inf` or `NaN`, in the same format as `T::from_str`.
+pub fn float<T: PrimitiveFloat>() -> Float<T> {
+    Float::new()
+}
+
+/// primitive float types parsed by `Float`.
+pub trait PrimitiveFloat: FromStr {}
+
+impl PrimitiveFloat for f32 {}
+impl PrimitiveFloat for f64 {}
+
+#[derive(Debug)]
+pub struct Float<T: PrimitiveFloat> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: PrimitiveFloat> Float<T> {
+    pub fn new() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: PrimitiveFloat> Default for Float<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S> ParserOnce<S> for Float<T>
+where
+    T: PrimitiveFloat,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    type Output = T;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, S> Parser<S> for Float<T>
+where
+    T: PrimitiveFloat,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut scanner = Scanner::new();
+        let mut segments = input.segments();
+
+        'segments: while let Some(segment) = segments.next(BytesDelta::ZERO).await {
+            for c in segment.chars() {
+                if !scanner.push(c) {
+                    break 'segments;
+                }
+            }
+        }
+
+        drop(segments);
+
+        let Some(len) = scanner.accepted else {
+            return fail((), input);
+        };
+
+        match scanner.buf[..len].parse() {
+            Ok(v) => done(v, input.advance(BytesDelta::from_bytes(len)).await),
+            Err(_) => fail((), input),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Signed,
+    Int,
+    /// 整数部のない小数点
+    Dot,
+    /// 整数部のある小数点
+    IntDot,
+    Frac,
+    Exp,
+    ExpSigned,
+    ExpDigits,
+    /// `inf`, `infinity`, `nan`
+    Word,
+}
+
+struct Scanner {
+    state: State,
+    buf: String,
+    word: usize,
+    accepted: Option<usize>,
+}
+
+impl Scanner {
+    fn new() -> Self {
+        Self {
+            state: State::Start,
+            buf: String::new(),
+            word: 0,
+            accepted: None,
+        }
+    }
+
+    /// return whether `c` may be a part of the float.
+    fn push(&mut self, c: char) -> bool {
+        let digit = c.is_ascii_digit();
+        let (next, accept) = match (self.state, c) {
+            (State::Start, '+' | '-') => (State::Signed, false),
+            (State::Start | State::Signed, 'i' | 'I' | 'n' | 'N') => (State::Word, false),
+            (State::Start | State::Signed, '.') => (State::Dot, false),
+            (State::Start | State::Signed | State::Int, _) if digit => (State::Int, true),
+            (State::Int, '.') => (State::IntDot, true),
+            (State::Dot | State::IntDot | State::Frac, _) if digit => (State::Frac, true),
+            (State::Int | State::IntDot | State::Frac, 'e' | 'E') => (State::Exp, false),
+            (State::Exp, '+' | '-') => (State::ExpSigned, false),
+            (State::Exp | State::ExpSigned | State::ExpDigits, _) if digit => {
+                (State::ExpDigits, true)
+            }
+            (State::Word, _) => return self.push_word(c),
+            _ => return false,
+        };
+
+        self.state = next;
+        self.buf.push(c);
+        if accept {
+            self.accepted = Some(self.buf.len());
+        }
+
+        if next == State::Word {
+            self.word = self.buf.len() - 1;
+        }
+
+        true
+    }
+
+    fn push_word(&mut self, c: char) -> bool {
+        let word = self.buf[self.word..].to_ascii_lowercase();
+        let Some(expected) = ["infinity", "nan"]
+            .into_iter()
+            .find(|w| w.starts_with(&word))
+        else {
+            return false;
+        };
+
+        if !expected[word.len()..].starts_with(c.to_ascii_lowercase()) {
+            return false;
+        }
+
+        self.buf.push(c);
+        if matches!(&expected[..=word.len()], "inf" | "infinity" | "nan") {
+            self.accepted = Some(self.buf.len());
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn parse_floats() {
+        pollster::block_on(async {
+            let (v, rest) = float::<f64>()
+                .parse(chain("-1.", "5e3x"))
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(v, -1.5e3);
+            assert_eq!(rest.into_inner().1, "x");
+
+            for (text, len) in [
+                (".5", 2),
+                ("1.", 2),
+                ("1.e2", 4),
+                ("1e", 1),
+                ("1e+", 1),
+                ("5", 1),
+            ] {
+                let (v, rest) = float::<f64>().parse(text).await.ok().unwrap();
+                assert_eq!(v, text[..len].parse::<f64>().unwrap());
+                assert_eq!(rest, &text[len..]);
+            }
+
+            assert_eq!(
+                float::<f32>().parse("-Infinity").await.ok().unwrap().0,
+                f32::NEG_INFINITY
+            );
+            assert_eq!(
+                float::<f64>().parse("infx").await.ok().unwrap(),
+                (f64::INFINITY, "x")
+            );
+            assert!(float::<f64>().parse("NaN").await.ok().unwrap().0.is_nan());
+            assert!(float::<f64>().parse("in").await.is_err());
+            assert!(float::<f64>().parse(".").await.is_err());
+            assert!(float::<f64>().parse("-").await.is_err());
+        })
+    }
+}
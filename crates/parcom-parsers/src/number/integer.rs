@@ -0,0 +1,297 @@
+use parcom_core::{
+    primitive::BytesDelta, Parser, ParserOnce, ParserResult, SegmentStream, Sequence,
+};
+use parcom_util::{done, error::Miss, fail};
+use std::marker::PhantomData;
+
+/// decimal integer with optional sign such as `-42`, in the same format as `T::from_str`.
+pub fn integer<T: PrimitiveInt>() -> Integer<T> {
+    Integer::new(Prefix::None, false)
+}
+
+/// hexadecimal integer prefixed with `0x` such as `0xFF_FF`.
+pub fn hex_integer<T: PrimitiveInt>() -> Integer<T> {
+    Integer::new(Prefix::Required(16), true)
+}
+
+/// octal integer prefixed with `0o` such as `0o7_55`.
+pub fn oct_integer<T: PrimitiveInt>() -> Integer<T> {
+    Integer::new(Prefix::Required(8), true)
+}
+
+/// binary integer prefixed with `0b` such as `0b1010_1010`.
+pub fn bin_integer<T: PrimitiveInt>() -> Integer<T> {
+    Integer::new(Prefix::Required(2), true)
+}
+
+/// integer prefixed with `0x`, `0o` or `0b`, or decimal integer without prefix.
+/// `_` are allowed between digits.
+pub fn prefixed_integer<T: PrimitiveInt>() -> Integer<T> {
+    Integer::new(Prefix::Any, true)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegerError {
+    /// the input does not start with an integer.
+    Invalid,
+    /// the integer does not fit in the type.
+    Overflow,
+}
+
+/// primitive integer types parsed by `Integer`.
+pub trait PrimitiveInt: Copy {
+    const SIGNED: bool;
+    const ZERO: Self;
+
+    /// return `self * radix + digit`, or `self * radix - digit` if `negative`.
+    fn push_digit(self, radix: u32, digit: u32, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_primitive_int {
+    ($signed: expr; $($t: ty),*) => {
+        $(
+            impl PrimitiveInt for $t {
+                const SIGNED: bool = $signed;
+                const ZERO: Self = 0;
+
+                fn push_digit(self, radix: u32, digit: u32, negative: bool) -> Option<Self> {
+                    let v = self.checked_mul(radix as $t)?;
+                    if negative {
+                        v.checked_sub(digit as $t)
+                    } else {
+                        v.checked_add(digit as $t)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_int!(false; u8, u16, u32, u64, u128, usize);
+impl_primitive_int!(true; i8, i16, i32, i64, i128, isize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prefix {
+    None,
+    Required(u32),
+    Any,
+}
+
+#[derive(Debug)]
+pub struct Integer<T: PrimitiveInt> {
+    prefix: Prefix,
+    separator: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: PrimitiveInt> Integer<T> {
+    fn new(prefix: Prefix, separator: bool) -> Self {
+        Self {
+            prefix,
+            separator,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, S> ParserOnce<S> for Integer<T>
+where
+    T: PrimitiveInt,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    type Output = T;
+    type Error = Miss<IntegerError>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, S> Parser<S> for Integer<T>
+where
+    T: PrimitiveInt,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut scanner = Scanner::<T>::new(self.prefix, self.separator);
+        let mut segments = input.segments();
+
+        'segments: while let Some(segment) = segments.next(BytesDelta::ZERO).await {
+            for c in segment.chars() {
+                match scanner.push(c) {
+                    Ok(true) => (),
+                    Ok(false) => break 'segments,
+                    Err(e) => {
+                        drop(segments);
+                        return fail(e, input);
+                    }
+                }
+            }
+        }
+
+        drop(segments);
+
+        match scanner.accepted {
+            Some((value, len)) => done(value, input.advance(BytesDelta::from_bytes(len)).await),
+            None => fail(IntegerError::Invalid, input),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Signed,
+    /// `0`を読んだ直後。接頭辞が続く可能性がある。
+    Zero,
+    /// 接頭辞を読んだ直後。
+    Prefixed,
+    Digits,
+}
+
+struct Scanner<T: PrimitiveInt> {
+    prefix: Prefix,
+    separator: bool,
+    state: State,
+    radix: u32,
+    negative: bool,
+    value: T,
+    len: usize,
+    /// (値, バイト長)
+    accepted: Option<(T, usize)>,
+}
+
+impl<T: PrimitiveInt> Scanner<T> {
+    fn new(prefix: Prefix, separator: bool) -> Self {
+        Self {
+            prefix,
+            separator,
+            state: State::Start,
+            radix: 10,
+            negative: false,
+            value: T::ZERO,
+            len: 0,
+            accepted: None,
+        }
+    }
+
+    /// return whether `c` is a part of the integer.
+    fn push(&mut self, c: char) -> Result<bool, IntegerError> {
+        match self.state {
+            State::Start | State::Signed => {
+                if self.state == State::Start && (c == '+' || (c == '-' && T::SIGNED)) {
+                    self.negative = c == '-';
+                    self.state = State::Signed;
+                } else if c == '0' && self.prefix != Prefix::None {
+                    self.state = State::Zero;
+                    if self.prefix == Prefix::Any {
+                        self.accept(1);
+                        return Ok(true);
+                    }
+                } else if matches!(self.prefix, Prefix::Required(_)) {
+                    return Ok(false);
+                } else {
+                    return self.push_digit(c);
+                }
+            }
+            State::Zero => {
+                let radix = match c {
+                    'x' | 'X' => 16,
+                    'o' | 'O' => 8,
+                    'b' | 'B' => 2,
+                    _ if self.prefix == Prefix::Any => return self.push_digit(c),
+                    _ => return Ok(false),
+                };
+
+                if self.prefix != Prefix::Any && self.prefix != Prefix::Required(radix) {
+                    return Ok(false);
+                }
+
+                self.radix = radix;
+                self.state = State::Prefixed;
+            }
+            State::Prefixed | State::Digits => return self.push_digit(c),
+        }
+
+        self.len += c.len_utf8();
+        Ok(true)
+    }
+
+    fn push_digit(&mut self, c: char) -> Result<bool, IntegerError> {
+        if c == '_' && self.separator && self.state != State::Start && self.state != State::Signed {
+            self.len += 1;
+            if self.state != State::Prefixed {
+                self.accept(0);
+            }
+            return Ok(true);
+        }
+
+        let Some(digit) = c.to_digit(self.radix) else {
+            return Ok(false);
+        };
+
+        self.value = self
+            .value
+            .push_digit(self.radix, digit, self.negative)
+            .ok_or(IntegerError::Overflow)?;
+        self.state = State::Digits;
+        self.accept(c.len_utf8());
+        Ok(true)
+    }
+
+    fn accept(&mut self, len: usize) {
+        self.len += len;
+        self.accepted = Some((self.value, self.len));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn parse_integers() {
+        pollster::block_on(async {
+            let (v, rest) = integer::<i8>()
+                .parse(chain("-1", "28;"))
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(v, -128);
+            assert_eq!(rest.into_inner().1, ";");
+
+            let Err((e, _)) = integer::<i8>().parse("128").await else {
+                panic!()
+            };
+            assert_eq!(e.0, IntegerError::Overflow);
+
+            let Err((e, _)) = integer::<u8>().parse("-1").await else {
+                panic!()
+            };
+            assert_eq!(e.0, IntegerError::Invalid);
+
+            assert_eq!(
+                hex_integer::<u16>().parse("0xff_FFg").await.ok().unwrap(),
+                (0xFFFF, "g")
+            );
+            assert!(hex_integer::<u16>().parse("0b1").await.is_err());
+            assert_eq!(
+                prefixed_integer::<u8>().parse("0b1_01").await.ok().unwrap(),
+                (5, "")
+            );
+            assert_eq!(
+                prefixed_integer::<u8>().parse("0o17").await.ok().unwrap(),
+                (15, "")
+            );
+            assert_eq!(
+                prefixed_integer::<u8>().parse("0_7").await.ok().unwrap(),
+                (7, "")
+            );
+            assert_eq!(
+                prefixed_integer::<u8>().parse("0xg").await.ok().unwrap(),
+                (0, "xg")
+            );
+        })
+    }
+}
@@ -1,7 +1,23 @@
 pub mod any;
 pub mod atom;
+pub mod atom_set;
+pub mod class;
+pub mod eof;
+pub mod satisfy;
+pub mod take_while;
 pub mod the;
 
 pub use any::{any_char, any_item};
-pub use atom::atom;
+pub use atom::{atom, atom_ignore_ascii_case, atom_ignore_case};
+pub use atom_set::{atom_set, keywords};
+pub use class::{
+    alnum, alpha, char_range, class, digit, general_category, hex_digit, none_of, one_of, space,
+    xid_continue, xid_start, CharClass,
+};
+pub use eof::eof;
+pub use satisfy::{satisfy_char, satisfy_item};
+pub use take_while::{
+    take_till_char, take_till_item, take_while1_char, take_while1_item, take_while_char,
+    take_while_item, take_while_m_n_char, take_while_m_n_item,
+};
 pub use the::{the_char, the_item};
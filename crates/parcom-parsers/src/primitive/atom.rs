@@ -1,4 +1,8 @@
-use parcom_core::{Parser, ParserOnce, ParserResult, SegmentStream, Sequence, SequenceSegment};
+use super::atom_set::AtomUnits;
+use parcom_core::{
+    primitive::BytesDelta, Parser, ParserOnce, ParserResult, SegmentStream, Sequence,
+    SequenceSegment,
+};
 use parcom_util::{done, error::Miss, fail};
 
 pub fn atom<P: AtomPattern>(pattern: P) -> Atom<P> {
@@ -43,7 +47,7 @@ where
                 let matched = s == remain;
                 drop(segments);
                 return if matched {
-                    done((), input.advance(remain.len()).await)
+                    done((), input.advance(self.pattern.pattern().len()).await)
                 } else {
                     fail(Miss(()), input)
                 };
@@ -64,6 +68,166 @@ where
     }
 }
 
+/// parse the pattern ignoring ASCII case.
+pub fn atom_ignore_ascii_case<P>(pattern: P) -> AtomIgnoreAsciiCase<P>
+where
+    P: AtomPattern,
+    P::Segment: AtomUnits<Unit = u8>,
+{
+    AtomIgnoreAsciiCase::new(pattern)
+}
+
+pub struct AtomIgnoreAsciiCase<P: AtomPattern>
+where
+    P::Segment: AtomUnits<Unit = u8>,
+{
+    pattern: P,
+}
+
+impl<P: AtomPattern> AtomIgnoreAsciiCase<P>
+where
+    P::Segment: AtomUnits<Unit = u8>,
+{
+    pub fn new(pattern: P) -> Self {
+        Self { pattern }
+    }
+}
+
+impl<P, S> ParserOnce<S> for AtomIgnoreAsciiCase<P>
+where
+    P: AtomPattern,
+    P::Segment: AtomUnits<Unit = u8>,
+    S: Sequence<Segment = P::Segment, Length = <P::Segment as SequenceSegment>::Length>,
+{
+    type Output = ();
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<P, S> Parser<S> for AtomIgnoreAsciiCase<P>
+where
+    P: AtomPattern,
+    P::Segment: AtomUnits<Unit = u8>,
+    S: Sequence<Segment = P::Segment, Length = <P::Segment as SequenceSegment>::Length>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let pattern = self.pattern.pattern().units();
+        let mut matched = 0;
+        let mut segments = input.segments();
+
+        while matched < pattern.len() {
+            let remain = pattern.len() - matched;
+            let Some(segment) = segments.next(P::Segment::units_len(remain)).await else {
+                break;
+            };
+
+            let units = segment.units();
+            let n = units.len().min(remain);
+            if !units[..n].eq_ignore_ascii_case(&pattern[matched..matched + n]) {
+                drop(segments);
+                return fail((), input);
+            }
+
+            matched += n;
+        }
+
+        drop(segments);
+
+        if matched < pattern.len() {
+            return fail((), input);
+        }
+
+        done((), input.advance(P::Segment::units_len(matched)).await)
+    }
+}
+
+/// parse the pattern comparing chars by their single-char case mappings.
+///
+/// this approximates Unicode simple case folding. chars whose case mapping expands to several chars,
+/// e.g. `ß` to `SS`, only match chars of the same single-char mappings, e.g. `ß` and `ẞ`.
+/// the matched input may differ in byte length from the pattern.
+pub fn atom_ignore_case<P: AtomPattern<Segment = str>>(pattern: P) -> AtomIgnoreCase<P> {
+    AtomIgnoreCase::new(pattern)
+}
+
+pub struct AtomIgnoreCase<P: AtomPattern<Segment = str>> {
+    pattern: P,
+}
+
+impl<P: AtomPattern<Segment = str>> AtomIgnoreCase<P> {
+    pub fn new(pattern: P) -> Self {
+        Self { pattern }
+    }
+}
+
+impl<P, S> ParserOnce<S> for AtomIgnoreCase<P>
+where
+    P: AtomPattern<Segment = str>,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    type Output = ();
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<P, S> Parser<S> for AtomIgnoreCase<P>
+where
+    P: AtomPattern<Segment = str>,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut expected = self.pattern.pattern().chars().peekable();
+        let mut consumed = 0;
+        let mut segments = input.segments();
+
+        while expected.peek().is_some() {
+            let Some(segment) = segments.next(BytesDelta::ZERO).await else {
+                break;
+            };
+
+            for c in segment.chars() {
+                let Some(e) = expected.next() else {
+                    break;
+                };
+
+                if case_key(c) != case_key(e) {
+                    drop(segments);
+                    return fail((), input);
+                }
+
+                consumed += c.len_utf8();
+            }
+        }
+
+        drop(segments);
+
+        if expected.peek().is_some() {
+            return fail((), input);
+        }
+
+        done((), input.advance(BytesDelta::from_bytes(consumed)).await)
+    }
+}
+
+/// key which equals between chars of the same case, derived from the single-char case mappings of std.
+/// not a complete case folding table.
+fn case_key(c: char) -> char {
+    fn single(mut iter: impl Iterator<Item = char>) -> Option<char> {
+        let c = iter.next()?;
+        iter.next().is_none().then_some(c)
+    }
+
+    // `ς`や`ſ`のように小文字化では畳まれない文字があるため、大文字化してから小文字化する。
+    let upper = single(c.to_uppercase()).unwrap_or(c);
+    single(upper.to_lowercase()).unwrap_or(upper)
+}
+
 pub trait AtomPattern {
     type Segment: ?Sized + SequenceSegment + PartialEq;
 
@@ -109,3 +273,45 @@ impl<T: 'static + PartialEq, const N: usize> AtomPattern for [T; N] {
         self.as_slice()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn match_across_segments() {
+        pollster::block_on(async {
+            let (_, rest) = atom("abc").parse(chain("a", "bcd")).await.ok().unwrap();
+            assert_eq!(rest.into_inner().1, "d");
+
+            let (_, rest) = atom("abc").parse(chain("ab", "c")).await.ok().unwrap();
+            assert_eq!(rest.into_inner().1, "");
+        })
+    }
+
+    #[test]
+    fn match_ignoring_case() {
+        pollster::block_on(async {
+            let parser = atom_ignore_ascii_case("select");
+            let (_, rest) = parser.parse(chain("SeL", "ect *")).await.ok().unwrap();
+            assert_eq!(rest.into_inner().1, " *");
+            assert!(parser.parse("selecț").await.is_err());
+
+            let parser = atom_ignore_ascii_case(b"GET".as_slice());
+            assert_eq!(
+                parser.parse(b"get /".as_slice()).await.ok().unwrap().1,
+                b" /"
+            );
+
+            let parser = atom_ignore_case("straße");
+            assert_eq!(parser.parse("STRAẞE!").await.ok().unwrap().1, "!");
+            assert!(parser.parse("STRASSE").await.is_err());
+
+            let parser = atom_ignore_case("ΣΟΦΟΣ");
+            let (_, rest) = parser.parse(chain("σοφ", "ος.")).await.ok().unwrap();
+            assert_eq!(rest.into_inner().1, ".");
+            assert!(parser.parse("σοφα").await.is_err());
+        })
+    }
+}
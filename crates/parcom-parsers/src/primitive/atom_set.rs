@@ -0,0 +1,199 @@
+use super::atom::AtomPattern;
+use parcom_core::{
+    primitive::BytesDelta, Parser, ParserOnce, ParserResult, SegmentStream, Sequence,
+    SequenceSegment,
+};
+use parcom_util::{done, error::Miss, fail};
+use std::marker::PhantomData;
+
+/// parse the longest pattern in `patterns`, and return its index.
+pub fn atom_set<P: AtomPattern>(patterns: impl IntoIterator<Item = P>) -> AtomSet<P::Segment, usize>
+where
+    P::Segment: AtomUnits,
+{
+    AtomSet::new(patterns.into_iter().enumerate().map(|(i, p)| (p, i)))
+}
+
+/// parse the longest pattern in `entries`, and return the value paired with it.
+pub fn keywords<P: AtomPattern, V: Clone>(
+    entries: impl IntoIterator<Item = (P, V)>,
+) -> AtomSet<P::Segment, V>
+where
+    P::Segment: AtomUnits,
+{
+    AtomSet::new(entries)
+}
+
+/// segment which can be split into units compared by the trie of `AtomSet`.
+pub trait AtomUnits: SequenceSegment {
+    type Unit: Clone + PartialEq;
+
+    fn units(&self) -> &[Self::Unit];
+    fn units_len(count: usize) -> Self::Length;
+}
+
+impl AtomUnits for str {
+    type Unit = u8;
+
+    fn units(&self) -> &[Self::Unit] {
+        self.as_bytes()
+    }
+
+    fn units_len(count: usize) -> Self::Length {
+        BytesDelta::from_bytes(count)
+    }
+}
+
+impl<T: 'static + Clone + PartialEq> AtomUnits for [T] {
+    type Unit = T;
+
+    fn units(&self) -> &[Self::Unit] {
+        self
+    }
+
+    fn units_len(count: usize) -> Self::Length {
+        count
+    }
+}
+
+/// set of patterns matched in one pass by a trie.
+pub struct AtomSet<G: ?Sized + AtomUnits, V: Clone> {
+    nodes: Vec<Node<G::Unit>>,
+    values: Vec<V>,
+    marker: PhantomData<fn(&G)>,
+}
+
+struct Node<U> {
+    children: Vec<(U, usize)>,
+    value: Option<usize>,
+}
+
+impl<G: ?Sized + AtomUnits, V: Clone> AtomSet<G, V> {
+    /// if the same pattern appears more than once, the last value is used.
+    pub fn new<P: AtomPattern<Segment = G>>(entries: impl IntoIterator<Item = (P, V)>) -> Self {
+        let mut me = Self {
+            nodes: vec![Node {
+                children: Vec::new(),
+                value: None,
+            }],
+            values: Vec::new(),
+            marker: PhantomData,
+        };
+
+        for (pattern, value) in entries {
+            let mut node = 0;
+            for unit in pattern.pattern().units() {
+                node = match me.child(node, unit) {
+                    Some(n) => n,
+                    None => {
+                        let n = me.nodes.len();
+                        me.nodes.push(Node {
+                            children: Vec::new(),
+                            value: None,
+                        });
+                        me.nodes[node].children.push((unit.clone(), n));
+                        n
+                    }
+                };
+            }
+
+            me.nodes[node].value = Some(me.values.len());
+            me.values.push(value);
+        }
+
+        me
+    }
+
+    fn child(&self, node: usize, unit: &G::Unit) -> Option<usize> {
+        self.nodes[node]
+            .children
+            .iter()
+            .find(|(u, _)| u == unit)
+            .map(|(_, n)| *n)
+    }
+}
+
+impl<G, V, S> ParserOnce<S> for AtomSet<G, V>
+where
+    G: ?Sized + AtomUnits,
+    V: Clone,
+    S: Sequence<Segment = G, Length = G::Length>,
+{
+    type Output = V;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<G, V, S> Parser<S> for AtomSet<G, V>
+where
+    G: ?Sized + AtomUnits,
+    V: Clone,
+    S: Sequence<Segment = G, Length = G::Length>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut node = 0;
+        let mut consumed = 0;
+        // (長さ, 値の添字)
+        let mut longest = self.nodes[0].value.map(|v| (0, v));
+        let mut segments = input.segments();
+
+        'segments: while !self.nodes[node].children.is_empty() {
+            let Some(segment) = segments.next(Default::default()).await else {
+                break;
+            };
+
+            for unit in segment.units() {
+                let Some(n) = self.child(node, unit) else {
+                    break 'segments;
+                };
+
+                node = n;
+                consumed += 1;
+                if let Some(v) = self.nodes[node].value {
+                    longest = Some((consumed, v));
+                }
+            }
+        }
+
+        drop(segments);
+
+        match longest {
+            Some((len, v)) => done(
+                self.values[v].clone(),
+                input.advance(G::units_len(len)).await,
+            ),
+            None => fail((), input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn match_longest_across_segments() {
+        pollster::block_on(async {
+            let parser = atom_set(["+", "+=", "++", "-"]);
+            let (i, rest) = parser.parse(chain("+", "=1")).await.ok().unwrap();
+            assert_eq!(i, 1);
+            assert_eq!(rest.into_inner().1, "1");
+
+            let (i, rest) = parser.parse("+-").await.ok().unwrap();
+            assert_eq!(i, 0);
+            assert_eq!(rest, "-");
+
+            assert!(parser.parse("*").await.is_err());
+
+            let parser = keywords([("if", true), ("in", false)]);
+            let (v, rest) = parser.parse("ifx").await.ok().unwrap();
+            assert!(v);
+            assert_eq!(rest, "x");
+            assert!(parser.parse("i").await.is_err());
+        })
+    }
+}
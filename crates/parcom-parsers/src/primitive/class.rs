@@ -0,0 +1,214 @@
+use super::satisfy::satisfy_char;
+use parcom_core::{primitive::BytesDelta, Parser, ParserOnce, ParserResult, Sequence};
+use parcom_util::error::Miss;
+use std::ops::RangeInclusive;
+
+pub use unicode_general_category::GeneralCategory;
+
+/// set of chars.
+pub trait CharClass {
+    fn contains(&self, c: char) -> bool;
+}
+
+impl<F: Fn(char) -> bool> CharClass for F {
+    fn contains(&self, c: char) -> bool {
+        self(c)
+    }
+}
+
+pub fn class<C: CharClass>(class: C) -> Class<C> {
+    Class::new(class)
+}
+
+/// parse a char contained in `chars`.
+pub fn one_of<T: AsRef<str>>(chars: T) -> Class<OneOf<T>> {
+    Class::new(OneOf { chars })
+}
+
+/// parse a char not contained in `chars`.
+pub fn none_of<T: AsRef<str>>(chars: T) -> Class<Negate<OneOf<T>>> {
+    one_of(chars).negate()
+}
+
+pub fn char_range(range: RangeInclusive<char>) -> Class<CharRange> {
+    Class::new(CharRange { range })
+}
+
+pub fn general_category(category: GeneralCategory) -> Class<Category> {
+    Class::new(Category { category })
+}
+
+pub fn xid_start() -> Class<fn(char) -> bool> {
+    Class::new(unicode_ident::is_xid_start)
+}
+
+pub fn xid_continue() -> Class<fn(char) -> bool> {
+    Class::new(unicode_ident::is_xid_continue)
+}
+
+/// `0-9`
+pub fn digit() -> Class<fn(char) -> bool> {
+    Class::new(|c| c.is_ascii_digit())
+}
+
+/// `a-z`, `A-Z`
+pub fn alpha() -> Class<fn(char) -> bool> {
+    Class::new(|c| c.is_ascii_alphabetic())
+}
+
+/// `0-9`, `a-z`, `A-Z`
+pub fn alnum() -> Class<fn(char) -> bool> {
+    Class::new(|c| c.is_ascii_alphanumeric())
+}
+
+/// `0-9`, `a-f`, `A-F`
+pub fn hex_digit() -> Class<fn(char) -> bool> {
+    Class::new(|c| c.is_ascii_hexdigit())
+}
+
+/// ASCII whitespace including line breaks.
+pub fn space() -> Class<fn(char) -> bool> {
+    Class::new(|c| c.is_ascii_whitespace())
+}
+
+/// parse a char contained in the class.
+pub struct Class<C: CharClass> {
+    class: C,
+}
+
+impl<C: CharClass> Class<C> {
+    pub fn new(class: C) -> Self {
+        Self { class }
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.class.contains(c)
+    }
+
+    pub fn union<D: CharClass>(self, other: Class<D>) -> Class<Union<C, D>> {
+        Class::new(Union {
+            first: self.class,
+            second: other.class,
+        })
+    }
+
+    pub fn negate(self) -> Class<Negate<C>> {
+        Class::new(Negate { class: self.class })
+    }
+
+    pub fn into_inner(self) -> C {
+        self.class
+    }
+}
+
+impl<C: CharClass> CharClass for Class<C> {
+    fn contains(&self, c: char) -> bool {
+        self.class.contains(c)
+    }
+}
+
+impl<C, S> ParserOnce<S> for Class<C>
+where
+    C: CharClass,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    type Output = char;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<C, S> Parser<S> for Class<C>
+where
+    C: CharClass,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        satisfy_char(|c| self.class.contains(c)).parse(input).await
+    }
+}
+
+pub struct OneOf<T: AsRef<str>> {
+    chars: T,
+}
+
+impl<T: AsRef<str>> CharClass for OneOf<T> {
+    fn contains(&self, c: char) -> bool {
+        self.chars.as_ref().contains(c)
+    }
+}
+
+pub struct CharRange {
+    range: RangeInclusive<char>,
+}
+
+impl CharClass for CharRange {
+    fn contains(&self, c: char) -> bool {
+        self.range.contains(&c)
+    }
+}
+
+pub struct Category {
+    category: GeneralCategory,
+}
+
+impl CharClass for Category {
+    fn contains(&self, c: char) -> bool {
+        unicode_general_category::get_general_category(c) == self.category
+    }
+}
+
+pub struct Union<C0: CharClass, C1: CharClass> {
+    first: C0,
+    second: C1,
+}
+
+impl<C0: CharClass, C1: CharClass> CharClass for Union<C0, C1> {
+    fn contains(&self, c: char) -> bool {
+        self.first.contains(c) || self.second.contains(c)
+    }
+}
+
+pub struct Negate<C: CharClass> {
+    class: C,
+}
+
+impl<C: CharClass> CharClass for Negate<C> {
+    fn contains(&self, c: char) -> bool {
+        !self.class.contains(c)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_char_in_class() {
+        pollster::block_on(async {
+            let parser = hex_digit().union(one_of("_"));
+            for (input, expected) in [("fA", Some('f')), ("_", Some('_')), ("g", None), ("", None)]
+            {
+                let result = parser.parse(input).await;
+                assert_eq!(result.ok().map(|(c, _)| c), expected);
+            }
+
+            let parser = space().negate();
+            let (c, rest) = parser.parse("x ").await.ok().unwrap();
+            assert_eq!((c, rest), ('x', " "));
+            assert!(parser.parse("\n").await.is_err());
+
+            let parser = general_category(GeneralCategory::UppercaseLetter);
+            assert_eq!(parser.parse("Ωa").await.ok().unwrap(), ('Ω', "a"));
+            assert!(parser.parse("ω").await.is_err());
+
+            let ident = xid_start().union(one_of("_"));
+            assert_eq!(ident.parse("変数").await.ok().unwrap(), ('変', "数"));
+            assert!(ident.parse("1").await.is_err());
+            assert_eq!(xid_continue().parse("1").await.ok().unwrap(), ('1', ""));
+            assert!(xid_continue().parse("-").await.is_err());
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use parcom_core::{Parser, ParserOnce, ParserResult, SegmentStream, Sequence, SequenceSegment};
+use parcom_util::{done, error::Miss, fail};
+
+/// succeed only at the end of the input.
+pub fn eof() -> Eof {
+    Eof::new()
+}
+
+#[derive(Debug)]
+pub struct Eof {
+    _priv: (),
+}
+
+impl Eof {
+    pub fn new() -> Self {
+        Self { _priv: () }
+    }
+}
+
+impl Default for Eof {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Sequence> ParserOnce<S> for Eof
+where
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Default + Ord,
+{
+    type Output = ();
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<S: Sequence> Parser<S> for Eof
+where
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Default + Ord,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut segments = input.segments();
+        let mut at_end = true;
+        while let Some(segment) = segments.next(Default::default()).await {
+            if segment.len() > Default::default() {
+                at_end = false;
+                break;
+            }
+        }
+
+        drop(segments);
+
+        if at_end {
+            done((), input)
+        } else {
+            fail((), input)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{primitive::the_char, ParserExtension};
+    use parcom_core::chain;
+
+    #[test]
+    fn succeed_only_at_end() {
+        pollster::block_on(async {
+            let parser = the_char('a').join(eof());
+            assert!(parser.parse("a").await.is_ok());
+            assert!(parser.parse("ab").await.is_err());
+
+            assert!(eof().parse(chain("", "")).await.is_ok());
+            assert!(eof().parse(chain("", "b")).await.is_err());
+        })
+    }
+}
@@ -0,0 +1,153 @@
+use parcom_core::{
+    primitive::BytesDelta, Parser, ParserOnce, ParserResult, SegmentStream, Sequence,
+};
+use parcom_util::{done, error::Miss, fail};
+use std::marker::PhantomData;
+
+pub fn satisfy_char<F: Fn(char) -> bool>(predicate: F) -> SatisfyChar<F> {
+    SatisfyChar::new(predicate)
+}
+
+/// parse a char which satisfies the predicate.
+pub struct SatisfyChar<F: Fn(char) -> bool> {
+    predicate: F,
+}
+
+impl<F: Fn(char) -> bool> SatisfyChar<F> {
+    pub fn new(predicate: F) -> Self {
+        Self { predicate }
+    }
+}
+
+impl<F, S> ParserOnce<S> for SatisfyChar<F>
+where
+    F: Fn(char) -> bool,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    type Output = char;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<F, S> Parser<S> for SatisfyChar<F>
+where
+    F: Fn(char) -> bool,
+    S: Sequence<Segment = str, Length = BytesDelta>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut segments = input.segments();
+        while let Some(segment) = segments.next(BytesDelta::ZERO).await {
+            let Some(c) = segment.chars().next() else {
+                continue;
+            };
+
+            let matched = (self.predicate)(c).then_some(c);
+            drop(segments);
+            return match matched {
+                Some(c) => done(c, input.advance(BytesDelta::from_char(c)).await),
+                None => fail((), input),
+            };
+        }
+
+        drop(segments);
+        fail((), input)
+    }
+}
+
+pub fn satisfy_item<T: 'static + Clone, F: Fn(&T) -> bool>(predicate: F) -> SatisfyItem<T, F> {
+    SatisfyItem::new(predicate)
+}
+
+/// parse an item which satisfies the predicate.
+pub struct SatisfyItem<T: 'static + Clone, F: Fn(&T) -> bool> {
+    predicate: F,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<T: 'static + Clone, F: Fn(&T) -> bool> SatisfyItem<T, F> {
+    pub fn new(predicate: F) -> Self {
+        Self {
+            predicate,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, F, S> ParserOnce<S> for SatisfyItem<T, F>
+where
+    T: 'static + Clone,
+    F: Fn(&T) -> bool,
+    S: Sequence<Segment = [T], Length = usize>,
+{
+    type Output = T;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, F, S> Parser<S> for SatisfyItem<T, F>
+where
+    T: 'static + Clone,
+    F: Fn(&T) -> bool,
+    S: Sequence<Segment = [T], Length = usize>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut segments = input.segments();
+
+        while let Some(segment) = segments.next(1).await {
+            let Some(item) = segment.first() else {
+                continue;
+            };
+
+            let matched = (self.predicate)(item).then(|| item.clone());
+            drop(segments);
+            return match matched {
+                Some(item) => done(item, input.advance(1).await),
+                None => fail((), input),
+            };
+        }
+
+        drop(segments);
+        fail((), input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn satisfy_first_char_or_item() {
+        pollster::block_on(async {
+            let parser = satisfy_char(|c| c.is_alphabetic());
+            let (out, rest) = parser.parse("éa").await.ok().unwrap();
+            assert_eq!(out, 'é');
+            assert_eq!(rest, "a");
+
+            let (out, _) = parser.parse(chain("", "a")).await.ok().unwrap();
+            assert_eq!(out, 'a');
+            assert!(parser.parse("1").await.is_err());
+            assert!(parser.parse("").await.is_err());
+
+            let parser = satisfy_item(|&b: &u8| b.is_ascii_digit());
+            let (out, rest) = parser.parse(b"1a".as_slice()).await.ok().unwrap();
+            assert_eq!(out, b'1');
+            assert_eq!(rest, b"a");
+
+            let (out, _) = parser
+                .parse(chain(b"".as_slice(), b"2".as_slice()))
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(out, b'2');
+            assert!(parser.parse(b"a".as_slice()).await.is_err());
+            assert!(parser.parse(b"".as_slice()).await.is_err());
+        })
+    }
+}
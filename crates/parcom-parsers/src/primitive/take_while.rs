@@ -0,0 +1,247 @@
+use parcom_core::{
+    primitive::BytesDelta, Parser, ParserOnce, ParserResult, RecognizeSequence, SegmentStream,
+    Sequence,
+};
+use parcom_util::{done, error::Miss, fail};
+use std::marker::PhantomData;
+
+/// parse chars while the predicate holds. never fails.
+pub fn take_while_char<F: Fn(char) -> bool>(predicate: F) -> TakeWhileChar<F> {
+    TakeWhileChar::new(predicate, 0, usize::MAX, true)
+}
+
+/// parse chars while the predicate holds. fails if no char is matched.
+pub fn take_while1_char<F: Fn(char) -> bool>(predicate: F) -> TakeWhileChar<F> {
+    TakeWhileChar::new(predicate, 1, usize::MAX, true)
+}
+
+/// parse at least `min` and at most `max` chars while the predicate holds.
+pub fn take_while_m_n_char<F: Fn(char) -> bool>(
+    min: usize,
+    max: usize,
+    predicate: F,
+) -> TakeWhileChar<F> {
+    TakeWhileChar::new(predicate, min, max, true)
+}
+
+/// parse chars until the predicate holds. never fails.
+pub fn take_till_char<F: Fn(char) -> bool>(predicate: F) -> TakeWhileChar<F> {
+    TakeWhileChar::new(predicate, 0, usize::MAX, false)
+}
+
+/// parse chars while `predicate(c) == expected`, and return the matched part of the input.
+pub struct TakeWhileChar<F: Fn(char) -> bool> {
+    predicate: F,
+    min: usize,
+    max: usize,
+    expected: bool,
+}
+
+impl<F: Fn(char) -> bool> TakeWhileChar<F> {
+    pub fn new(predicate: F, min: usize, max: usize, expected: bool) -> Self {
+        Self {
+            predicate,
+            min,
+            max,
+            expected,
+        }
+    }
+}
+
+impl<F, S> ParserOnce<S> for TakeWhileChar<F>
+where
+    F: Fn(char) -> bool,
+    S: RecognizeSequence<Segment = str, Length = BytesDelta>,
+{
+    type Output = S::Span;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<F, S> Parser<S> for TakeWhileChar<F>
+where
+    F: Fn(char) -> bool,
+    S: RecognizeSequence<Segment = str, Length = BytesDelta>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut len = 0;
+        let mut count = 0;
+        let mut segments = input.segments();
+
+        'segments: while count < self.max {
+            let Some(segment) = segments.next(BytesDelta::ZERO).await else {
+                break;
+            };
+
+            for (i, c) in segment.char_indices() {
+                if count == self.max || (self.predicate)(c) != self.expected {
+                    len += i;
+                    break 'segments;
+                }
+                count += 1;
+            }
+
+            len += segment.len();
+        }
+
+        drop(segments);
+
+        if count < self.min {
+            return fail((), input);
+        }
+
+        // 数え終えてから一度に進め、一致した部分を入力から借用する。
+        let (recorder, mark) = input.record();
+        let recorder = recorder.advance(BytesDelta::from_bytes(len)).await;
+        let (matched, rest) = S::recognize(recorder, mark);
+        done(matched, rest)
+    }
+}
+
+/// parse items while the predicate holds. never fails.
+pub fn take_while_item<T, F>(predicate: F) -> TakeWhileItem<T, F>
+where
+    T: 'static + Clone,
+    F: Fn(&T) -> bool,
+{
+    TakeWhileItem::new(predicate, 0, usize::MAX, true)
+}
+
+/// parse items while the predicate holds. fails if no item is matched.
+pub fn take_while1_item<T, F>(predicate: F) -> TakeWhileItem<T, F>
+where
+    T: 'static + Clone,
+    F: Fn(&T) -> bool,
+{
+    TakeWhileItem::new(predicate, 1, usize::MAX, true)
+}
+
+/// parse at least `min` and at most `max` items while the predicate holds.
+pub fn take_while_m_n_item<T, F>(min: usize, max: usize, predicate: F) -> TakeWhileItem<T, F>
+where
+    T: 'static + Clone,
+    F: Fn(&T) -> bool,
+{
+    TakeWhileItem::new(predicate, min, max, true)
+}
+
+/// parse items until the predicate holds. never fails.
+pub fn take_till_item<T, F>(predicate: F) -> TakeWhileItem<T, F>
+where
+    T: 'static + Clone,
+    F: Fn(&T) -> bool,
+{
+    TakeWhileItem::new(predicate, 0, usize::MAX, false)
+}
+
+/// parse items while `predicate(item) == expected`, and return the matched part of the input.
+pub struct TakeWhileItem<T: 'static + Clone, F: Fn(&T) -> bool> {
+    predicate: F,
+    min: usize,
+    max: usize,
+    expected: bool,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<T: 'static + Clone, F: Fn(&T) -> bool> TakeWhileItem<T, F> {
+    pub fn new(predicate: F, min: usize, max: usize, expected: bool) -> Self {
+        Self {
+            predicate,
+            min,
+            max,
+            expected,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, F, S> ParserOnce<S> for TakeWhileItem<T, F>
+where
+    T: 'static + Clone,
+    F: Fn(&T) -> bool,
+    S: RecognizeSequence<Segment = [T], Length = usize>,
+{
+    type Output = S::Span;
+    type Error = Miss<()>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<T, F, S> Parser<S> for TakeWhileItem<T, F>
+where
+    T: 'static + Clone,
+    F: Fn(&T) -> bool,
+    S: RecognizeSequence<Segment = [T], Length = usize>,
+{
+    async fn parse(&self, mut input: S) -> ParserResult<S, Self> {
+        let mut len = 0;
+        let mut segments = input.segments();
+
+        while len < self.max {
+            let Some(segment) = segments.next(0).await else {
+                break;
+            };
+
+            let limit = segment.len().min(self.max - len);
+            let matched = segment[..limit]
+                .iter()
+                .position(|item| (self.predicate)(item) != self.expected)
+                .unwrap_or(limit);
+
+            len += matched;
+
+            if matched < segment.len() {
+                break;
+            }
+        }
+
+        drop(segments);
+
+        if len < self.min {
+            return fail((), input);
+        }
+
+        let (recorder, mark) = input.record();
+        let recorder = recorder.advance(len).await;
+        let (matched, rest) = S::recognize(recorder, mark);
+        done(matched, rest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn take_across_segments() {
+        pollster::block_on(async {
+            let parser = take_while_char(|c| c.is_ascii_alphabetic());
+            let (out, rest) = parser.parse(chain("ab", "c1")).await.ok().unwrap();
+            assert_eq!(out, "abc");
+            assert_eq!(rest.into_inner().1, "1");
+
+            let input = "abc1";
+            let (out, rest) = parser.parse(input).await.ok().unwrap();
+            assert_eq!((out, out.as_ptr()), ("abc", input.as_ptr()));
+            assert_eq!(rest, "1");
+
+            let parser = take_while_m_n_char(1, 2, |c| c.is_ascii_alphabetic());
+            let (out, _) = parser.parse(chain("a", "bc")).await.ok().unwrap();
+            assert_eq!(out, "ab");
+
+            let parser = take_while1_char(|c| c.is_ascii_digit());
+            assert!(parser.parse("abc").await.is_err());
+
+            let parser = take_till_item(|&b| b == b',');
+            let (out, rest) = parser.parse(b"ab,c".as_slice()).await.ok().unwrap();
+            assert_eq!(out, b"ab");
+            assert_eq!(rest, b",c");
+        })
+    }
+}
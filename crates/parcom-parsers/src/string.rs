@@ -0,0 +1,489 @@
+use parcom_core::{
+    primitive::BytesDelta, Parser, ParserOnce, ParserResult, RecognizeSequence, SegmentStream,
+    Sequence,
+};
+use parcom_util::{
+    done,
+    error::{Fatal, Miss},
+    fail, Either,
+};
+use std::{borrow::Cow, marker::PhantomData};
+
+/// quoted string with `"` quotes and standard escapes: `\n`, `\r`, `\t`, `\0`, `\\`, `\"`, `\'`, `\u{...}` and `\uXXXX`.
+///
+/// the output borrows the input if the input is in memory and the string has no escapes.
+pub fn string_literal() -> StringLiteral<Miss<StringError>> {
+    StringLiteral::new()
+}
+
+/// quoted string in which `\` has no special meaning.
+///
+/// the opening quote may be preceded by `#`s, and then the string is closed by the quote followed by the same count of `#`s,
+/// e.g. `#"a"b"#`. a prefix such as `r` of Rust is not parsed; use `preceded(atom("r"), raw_string_literal())`.
+pub fn raw_string_literal() -> StringLiteral<Miss<StringError>> {
+    string_literal().raw()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringError {
+    /// the closing quote is missing.
+    Unterminated,
+    /// unknown escape, invalid code point or unpaired surrogate.
+    InvalidEscape,
+}
+
+/// span of a string literal recognized from `RecognizeSequence`.
+pub trait LiteralSpan {
+    type Str;
+
+    fn as_str(&self) -> &str;
+    fn slice(self, start: usize, end: usize) -> Self::Str;
+    fn owned(value: String) -> Self::Str;
+}
+
+impl<'a> LiteralSpan for &'a str {
+    type Str = Cow<'a, str>;
+
+    fn as_str(&self) -> &str {
+        self
+    }
+
+    fn slice(self, start: usize, end: usize) -> Self::Str {
+        Cow::Borrowed(&self[start..end])
+    }
+
+    fn owned(value: String) -> Self::Str {
+        Cow::Owned(value)
+    }
+}
+
+impl LiteralSpan for String {
+    type Str = Cow<'static, str>;
+
+    fn as_str(&self) -> &str {
+        self
+    }
+
+    fn slice(mut self, start: usize, end: usize) -> Self::Str {
+        self.truncate(end);
+        self.drain(..start);
+        Cow::Owned(self)
+    }
+
+    fn owned(value: String) -> Self::Str {
+        Cow::Owned(value)
+    }
+}
+
+/// `Error` is `Either::First` if the input does not start with a quote, otherwise `Either::Last` of `E`.
+#[derive(Debug)]
+pub struct StringLiteral<E: parcom_core::ParseError + From<StringError>> {
+    quotes: Vec<char>,
+    escapes: Vec<(char, char)>,
+    unicode_escapes: bool,
+    raw: bool,
+    marker: PhantomData<fn() -> E>,
+}
+
+impl<E: parcom_core::ParseError + From<StringError>> StringLiteral<E> {
+    pub fn new() -> Self {
+        Self {
+            quotes: vec!['"'],
+            escapes: vec![
+                ('n', '\n'),
+                ('r', '\r'),
+                ('t', '\t'),
+                ('0', '\0'),
+                ('\\', '\\'),
+                ('"', '"'),
+                ('\'', '\''),
+            ],
+            unicode_escapes: true,
+            raw: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// set the chars which open a string. a string is closed by the same char as opened.
+    pub fn quotes(mut self, quotes: impl IntoIterator<Item = char>) -> Self {
+        self.quotes = quotes.into_iter().collect();
+        self
+    }
+
+    /// add or replace the escape `\c`.
+    pub fn escape(mut self, c: char, replacement: char) -> Self {
+        self.escapes.retain(|(e, _)| *e != c);
+        self.escapes.push((c, replacement));
+        self
+    }
+
+    /// remove all escapes except for the quotes and unicode escapes.
+    pub fn clear_escapes(mut self) -> Self {
+        self.escapes.clear();
+        self
+    }
+
+    pub fn unicode_escapes(mut self, enabled: bool) -> Self {
+        self.unicode_escapes = enabled;
+        self
+    }
+
+    /// disable escapes and allow `#` delimiters around the quotes.
+    pub fn raw(mut self) -> Self {
+        self.raw = true;
+        self
+    }
+
+    /// make errors after the opening quote terminal.
+    pub fn fatal(self) -> StringLiteral<Fatal<StringError>> {
+        StringLiteral {
+            quotes: self.quotes,
+            escapes: self.escapes,
+            unicode_escapes: self.unicode_escapes,
+            raw: self.raw,
+            marker: PhantomData,
+        }
+    }
+
+    fn error(&self, state: State) -> Either<Miss<()>, E> {
+        match state {
+            State::Open => Either::First(Miss(())),
+            _ => Either::Last(StringError::Unterminated.into()),
+        }
+    }
+}
+
+impl<E: parcom_core::ParseError + From<StringError>> Default for StringLiteral<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, S> ParserOnce<S> for StringLiteral<E>
+where
+    E: parcom_core::ParseError + From<StringError>,
+    S: RecognizeSequence<Segment = str, Length = BytesDelta>,
+    S::Span: LiteralSpan,
+{
+    type Output = <S::Span as LiteralSpan>::Str;
+    type Error = Either<Miss<()>, E>;
+
+    async fn parse_once(self, input: S) -> ParserResult<S, Self> {
+        self.parse(input).await
+    }
+}
+
+impl<E, S> Parser<S> for StringLiteral<E>
+where
+    E: parcom_core::ParseError + From<StringError>,
+    S: RecognizeSequence<Segment = str, Length = BytesDelta>,
+    S::Span: LiteralSpan,
+{
+    async fn parse(&self, input: S) -> ParserResult<S, Self> {
+        let (mut recorder, mark) = input.record();
+        let mut scanner = Scanner::new(self);
+        let mut len = 0;
+        let mut segments = recorder.segments();
+
+        // 1回目の走査では終端と妥当性だけを確認し、エスケープがある場合のみ2回目で展開する。
+        let result = 'scan: loop {
+            let Some(segment) = segments.next(BytesDelta::ZERO).await else {
+                break Err(self.error(scanner.state));
+            };
+
+            for c in segment.chars() {
+                len += c.len_utf8();
+                match scanner.push(c, None) {
+                    Ok(false) => (),
+                    Ok(true) => break 'scan Ok(()),
+                    Err(Some(e)) => break 'scan Err(Either::Last(e.into())),
+                    Err(None) => break 'scan Err(Either::First(Miss(()))),
+                }
+            }
+        };
+
+        drop(segments);
+
+        if let Err(e) = result {
+            let (_, input) = S::recognize(recorder, mark);
+            return fail(e, input);
+        }
+
+        let recorder = recorder.advance(BytesDelta::from_bytes(len)).await;
+        let (span, rest) = S::recognize(recorder, mark);
+        let quote = scanner.quote.len_utf8() + scanner.hashes;
+
+        let output = if scanner.escaped {
+            let mut out = String::new();
+            let mut decoder = Scanner::new(self);
+            for c in span.as_str().chars() {
+                decoder
+                    .push(c, Some(&mut out))
+                    .expect("the literal was validated by the first scan.");
+            }
+            S::Span::owned(out)
+        } else {
+            span.slice(quote, len - quote)
+        };
+
+        done(output, rest)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Open,
+    Content,
+    Escape,
+    /// `\u`の直後
+    Unicode,
+    /// `\u{...}`
+    Braced {
+        value: u32,
+        digits: u32,
+    },
+    /// `\uXXXX`
+    Fixed {
+        value: u32,
+        digits: u32,
+    },
+    /// rawで閉じ引用符の後に`#`を`seen`個読んだ状態。
+    Closing {
+        seen: usize,
+    },
+    /// 上位サロゲートの直後。`\uXXXX`の下位サロゲートが続く必要がある。
+    LowBackslash {
+        high: u32,
+    },
+    LowU {
+        high: u32,
+    },
+    Low {
+        high: u32,
+        value: u32,
+        digits: u32,
+    },
+}
+
+struct Scanner<'a, E: parcom_core::ParseError + From<StringError>> {
+    config: &'a StringLiteral<E>,
+    state: State,
+    quote: char,
+    /// count of `#`s before the opening quote.
+    hashes: usize,
+    escaped: bool,
+}
+
+impl<'a, E: parcom_core::ParseError + From<StringError>> Scanner<'a, E> {
+    fn new(config: &'a StringLiteral<E>) -> Self {
+        Self {
+            config,
+            state: State::Open,
+            quote: '"',
+            hashes: 0,
+            escaped: false,
+        }
+    }
+
+    /// return whether the string is closed by `c`.
+    /// `Err(None)` means that the input does not start with a quote.
+    fn push(&mut self, c: char, mut out: Option<&mut String>) -> Result<bool, Option<StringError>> {
+        let mut emit = |c: char| {
+            if let Some(out) = out.as_deref_mut() {
+                out.push(c);
+            }
+        };
+        let invalid = Err(Some(StringError::InvalidEscape));
+
+        self.state = match self.state {
+            State::Open if self.config.quotes.contains(&c) => {
+                self.quote = c;
+                State::Content
+            }
+            State::Open if c == '#' && self.config.raw => {
+                self.hashes += 1;
+                State::Open
+            }
+            State::Open => return Err(None),
+            State::Content if c == self.quote && self.hashes == 0 => return Ok(true),
+            State::Content if c == self.quote => State::Closing { seen: 0 },
+            State::Closing { seen } if c == '#' => {
+                if seen + 1 == self.hashes {
+                    return Ok(true);
+                }
+                State::Closing { seen: seen + 1 }
+            }
+            State::Closing { seen } => {
+                // `#`が足りなければ、読んだ引用符と`#`は内容の一部である。
+                emit(self.quote);
+                (0..seen).for_each(|_| emit('#'));
+                if c == self.quote {
+                    State::Closing { seen: 0 }
+                } else {
+                    emit(c);
+                    State::Content
+                }
+            }
+            State::Content if c == '\\' && !self.config.raw => {
+                self.escaped = true;
+                State::Escape
+            }
+            State::Content => {
+                emit(c);
+                State::Content
+            }
+            State::Escape if c == 'u' && self.config.unicode_escapes => State::Unicode,
+            State::Escape => {
+                let replacement = match self.config.escapes.iter().find(|(e, _)| *e == c) {
+                    Some((_, r)) => *r,
+                    None if c == self.quote => c,
+                    None => return invalid,
+                };
+                emit(replacement);
+                State::Content
+            }
+            State::Unicode if c == '{' => State::Braced {
+                value: 0,
+                digits: 0,
+            },
+            State::Unicode => match c.to_digit(16) {
+                Some(d) => State::Fixed {
+                    value: d,
+                    digits: 1,
+                },
+                None => return invalid,
+            },
+            State::Braced { value, digits } if c == '}' => {
+                match (digits > 0).then(|| char::from_u32(value)).flatten() {
+                    Some(c) => emit(c),
+                    None => return invalid,
+                }
+                State::Content
+            }
+            State::Braced { value, digits } => match c.to_digit(16) {
+                Some(d) if digits < 6 => State::Braced {
+                    value: value * 16 + d,
+                    digits: digits + 1,
+                },
+                _ => return invalid,
+            },
+            State::Fixed { value, digits } => {
+                let Some(d) = c.to_digit(16) else {
+                    return invalid;
+                };
+                let value = value * 16 + d;
+
+                if digits + 1 < 4 {
+                    State::Fixed {
+                        value,
+                        digits: digits + 1,
+                    }
+                } else if (0xD800..0xDC00).contains(&value) {
+                    State::LowBackslash { high: value }
+                } else {
+                    match char::from_u32(value) {
+                        Some(c) => emit(c),
+                        None => return invalid,
+                    }
+                    State::Content
+                }
+            }
+            State::LowBackslash { high } if c == '\\' => State::LowU { high },
+            State::LowU { high } if c == 'u' => State::Low {
+                high,
+                value: 0,
+                digits: 0,
+            },
+            State::LowBackslash { .. } | State::LowU { .. } => return invalid,
+            State::Low {
+                high,
+                value,
+                digits,
+            } => {
+                let Some(d) = c.to_digit(16) else {
+                    return invalid;
+                };
+                let value = value * 16 + d;
+
+                if digits + 1 < 4 {
+                    State::Low {
+                        high,
+                        value,
+                        digits: digits + 1,
+                    }
+                } else if (0xDC00..0xE000).contains(&value) {
+                    let c = 0x10000 + ((high - 0xD800) << 10) + (value - 0xDC00);
+                    emit(char::from_u32(c).unwrap());
+                    State::Content
+                } else {
+                    return invalid;
+                }
+            }
+        };
+
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::chain;
+
+    #[test]
+    fn parse_string_literals() {
+        pollster::block_on(async {
+            let (s, rest) = string_literal().parse("\"abc\"x").await.ok().unwrap();
+            assert!(matches!(s, Cow::Borrowed("abc")));
+            assert_eq!(rest, "x");
+
+            let text = r#""a\n\"\u{1F600}😀é""#;
+            let (s, rest) = string_literal().parse(text).await.ok().unwrap();
+            assert_eq!(s, "a\n\"😀😀é");
+            assert_eq!(rest, "");
+
+            let (s, _) = raw_string_literal()
+                .quotes(['\''])
+                .parse(r"'a\n'")
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(s, r"a\n");
+
+            let (s, rest) = raw_string_literal()
+                .parse(chain(r##"##"a"#""##, r###""b"##c"###))
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!((s.as_ref(), rest.into_inner().1), (r##"a"#""b"##, "c"));
+
+            let Err((e, _)) = raw_string_literal().parse(r##"#"a""##).await else {
+                panic!()
+            };
+            assert!(matches!(e, Either::Last(Miss(StringError::Unterminated))));
+            assert!(string_literal().parse(r##"#"a"#"##).await.is_err());
+
+            let (s, _) = string_literal()
+                .parse(chain("\"a\\", "tb\""))
+                .await
+                .ok()
+                .unwrap();
+            assert_eq!(s, "a\tb");
+
+            let Err((e, _)) = string_literal().parse("\"abc").await else {
+                panic!()
+            };
+            assert!(matches!(e, Either::Last(Miss(StringError::Unterminated))));
+
+            let Err((e, _)) = string_literal().fatal().parse(r#""\ud83d""#).await else {
+                panic!()
+            };
+            assert!(matches!(e, Either::Last(Fatal(StringError::InvalidEscape))));
+
+            let Err((e, _)) = string_literal().fatal().parse("abc").await else {
+                panic!()
+            };
+            assert!(matches!(e, Either::First(Miss(()))));
+        })
+    }
+}
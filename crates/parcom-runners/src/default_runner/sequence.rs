@@ -1,10 +1,13 @@
 mod advance;
 mod segments;
 
-use parcom_core::Sequence;
+use parcom_core::{RecognizeSequence, RecordSegment, Recording, Sequence};
 use parcom_internals::future::notify::Notify;
 use parcom_sequence_core::{SequenceBuffer, SequenceBuilder, SequenceSource};
-use std::sync::{atomic::AtomicBool, Arc};
+use std::{
+    ops::Sub,
+    sync::{atomic::AtomicBool, Arc},
+};
 
 pub use advance::DefaultSequenceAdvance;
 pub use segments::{DefaultSegments, DefaultSegmentsNext};
@@ -71,3 +74,131 @@ where
         DefaultSequenceAdvance::new(self, delta)
     }
 }
+
+impl<S, B> RecognizeSequence for DefaultSequence<S, B>
+where
+    S: SequenceSource,
+    B: SequenceBuilder<S>,
+    B::Segment: RecordSegment<Length = B::Length>,
+    B::Length: Copy + Default + Ord + Sub<Output = B::Length>,
+{
+    // セグメントはadvance後に解放される可能性があるため、消費した部分を複製して保持する。
+    type Recorder = Recording<Self>;
+    type Mark = ();
+    type Span = <B::Segment as RecordSegment>::Owned;
+
+    fn record(self) -> (Self::Recorder, Self::Mark) {
+        (Recording::new(self), ())
+    }
+
+    fn recognize(recorder: Self::Recorder, _: Self::Mark) -> (Self::Span, Self) {
+        recorder.into_parts()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parcom_core::{primitive::BytesDelta, Never, Parser};
+    use parcom_parsers::{primitive::atom, ParserExtension};
+    use parcom_sequence_core::{LoadInfo, SequenceControl, SequenceLoader};
+    use std::sync::atomic::Ordering;
+
+    /// buffer holding all segments from the start.
+    struct Segments {
+        segments: Vec<&'static str>,
+        head: usize,
+    }
+
+    impl SequenceBuffer for Segments {
+        type Length = BytesDelta;
+        type Segment = str;
+        type Iter<'a> = std::vec::IntoIter<&'a str>;
+
+        fn advance(&mut self, length: BytesDelta) -> BytesDelta {
+            let mut remain = length.to_bytes();
+            while let Some(segment) = self.segments.first_mut() {
+                let len = segment.len() - self.head;
+                if remain < len {
+                    self.head += remain;
+                    return BytesDelta::ZERO;
+                }
+                remain -= len;
+                self.head = 0;
+                self.segments.remove(0);
+            }
+            BytesDelta::from_bytes(remain)
+        }
+
+        fn segments(&self) -> Self::Iter<'_> {
+            let mut segments = self.segments.clone();
+            if let Some(first) = segments.first_mut() {
+                *first = &first[self.head..];
+            }
+            segments.into_iter()
+        }
+    }
+
+    struct Loaded;
+
+    impl SequenceLoader for Loaded {
+        type Length = BytesDelta;
+        type Segment = str;
+        type Error = Never;
+        type Load<'a> = std::future::Pending<Result<LoadInfo, Never>>;
+
+        fn force_commit(&mut self) {}
+
+        fn load(&mut self) -> Self::Load<'_> {
+            std::future::pending()
+        }
+    }
+
+    struct Finished;
+
+    impl SequenceSource for Finished {
+        type Item = u8;
+        type Error = Never;
+        type Next<'a, C>
+            = std::future::Ready<C::Result>
+        where
+            C: 'a + SequenceControl<Item = u8, Error = Never>;
+
+        fn next<'a, C>(&'a mut self, control: C, _: usize) -> Self::Next<'a, C>
+        where
+            C: 'a + SequenceControl<Item = u8, Error = Never>,
+        {
+            std::future::ready(control.finish())
+        }
+    }
+
+    struct Builder;
+
+    impl SequenceBuilder<Finished> for Builder {
+        type Length = BytesDelta;
+        type Segment = str;
+        type Buffer = Segments;
+        type Loader = Loaded;
+
+        fn build(&self, _: Finished) -> (Self::Buffer, Self::Loader) {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn recognize_across_segments() {
+        pollster::block_on(async {
+            let buffer = Segments {
+                segments: vec!["a", "bc"],
+                head: 0,
+            };
+            let sequence = DefaultSequence::<Finished, Builder>::new(buffer, Loaded);
+            sequence.inner.done_flag.store(true, Ordering::SeqCst);
+
+            let parser = atom("ab").recognize();
+            let (span, rest) = parser.parse(sequence).await.ok().unwrap();
+            assert_eq!(span, "ab");
+            assert_eq!(rest.inner.buffer.segments().collect::<Vec<_>>(), ["c"]);
+        })
+    }
+}
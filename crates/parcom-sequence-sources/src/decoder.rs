@@ -0,0 +1,284 @@
+pub mod latin1;
+pub mod utf16;
+
+use parcom_sequence_core::{BufferWriter, SequenceControl, SequenceSource};
+use std::marker::PhantomData;
+
+pub use latin1::Latin1;
+pub use utf16::{ByteOrder, Utf16};
+
+/// encoding which is transcoded into UTF-8 by `Decoder`.
+pub trait Decode {
+    /// decode the head of `src` into `out`, and return the count of consumed bytes.
+    /// bytes not consumed are passed again with the following bytes at the next call.
+    ///
+    /// return `Err(offset)` if `src` has an invalid sequence at `offset`.
+    /// the chars before the invalid sequence are left in `out`.
+    fn decode(&mut self, src: &[u8], out: &mut String) -> Result<usize, usize>;
+}
+
+#[derive(Debug)]
+pub enum DecodeError<E> {
+    /// `offset` is the byte offset in the source, not in the decoded sequence.
+    InvalidSequence {
+        offset: usize,
+    },
+    Inner(E),
+}
+
+/// source which transcodes bytes of `source` into UTF-8 bytes.
+pub struct Decoder<S: SequenceSource<Item = u8>, D: Decode> {
+    source: S,
+    state: State<D>,
+}
+
+struct State<D: Decode> {
+    decoding: D,
+    /// bytes read from the source but not decoded yet.
+    buf: Vec<u8>,
+    out: String,
+    /// decoded bytes which the control could not accept yet.
+    undelivered: Vec<u8>,
+    /// source byte offset of an invalid sequence, reported after `undelivered` is delivered.
+    invalid: Option<usize>,
+    /// byte offset in the source of the head of `buf`.
+    offset: usize,
+}
+
+impl<S: SequenceSource<Item = u8>, D: Decode> Decoder<S, D> {
+    pub fn new(source: S, decoding: D) -> Self {
+        Self {
+            source,
+            state: State {
+                decoding,
+                buf: Vec::new(),
+                out: String::new(),
+                undelivered: Vec::new(),
+                invalid: None,
+                offset: 0,
+            },
+        }
+    }
+}
+
+impl<S: SequenceSource<Item = u8>, D: Decode> SequenceSource for Decoder<S, D> {
+    type Item = u8;
+    type Error = DecodeError<S::Error>;
+
+    type Next<'a, C>
+        = S::Next<'a, Control<'a, D, C, S::Error>>
+    where
+        Self: 'a,
+        C: 'a + SequenceControl<Item = Self::Item, Error = Self::Error>;
+
+    fn next<'a, C>(&'a mut self, control: C, size_hint: usize) -> Self::Next<'a, C>
+    where
+        C: 'a + SequenceControl<Item = Self::Item, Error = Self::Error>,
+    {
+        let control = Control {
+            state: &mut self.state,
+            control,
+            _phantom: PhantomData,
+        };
+
+        self.source.next(control, size_hint)
+    }
+}
+
+pub struct Control<'a, D: Decode, C, E> {
+    state: &'a mut State<D>,
+    control: C,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<'a, D, C, E> SequenceControl for Control<'a, D, C, E>
+where
+    D: Decode,
+    C: SequenceControl<Item = u8, Error = DecodeError<E>>,
+{
+    type Item = u8;
+    type Result = C::Result;
+    type Error = E;
+    type Writer = Writer<'a, D, C, E>;
+
+    fn request_writer(self, min_capacity: usize) -> Self::Writer {
+        let pending = self.state.buf.len();
+        self.state.buf.reserve(min_capacity);
+
+        Writer {
+            pending,
+            state: self.state,
+            control: self.control,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn cancel(self, err: E) -> Self::Result {
+        self.control.cancel(DecodeError::Inner(err))
+    }
+
+    fn finish(self) -> Self::Result {
+        // 渡しきれていない出力があれば、終了する前に渡す。
+        if !self.state.undelivered.is_empty() {
+            return deliver(&mut self.state.undelivered, self.control);
+        }
+
+        if let Some(offset) = self.state.invalid {
+            self.control.cancel(DecodeError::InvalidSequence { offset })
+        } else if self.state.buf.is_empty() {
+            self.control.finish()
+        } else {
+            let offset = self.state.offset;
+            self.control.cancel(DecodeError::InvalidSequence { offset })
+        }
+    }
+}
+
+pub struct Writer<'a, D: Decode, C, E> {
+    /// count of bytes in `state.buf` left by the previous load.
+    pending: usize,
+    state: &'a mut State<D>,
+    control: C,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<'a, D, C, E> BufferWriter for Writer<'a, D, C, E>
+where
+    D: Decode,
+    C: SequenceControl<Item = u8, Error = DecodeError<E>>,
+{
+    type Segment = [u8];
+    type Item = u8;
+    type Result = C::Result;
+    type Error = E;
+
+    fn capacity(&self) -> usize {
+        self.state.buf.capacity() - self.pending
+    }
+
+    fn len(&self) -> usize {
+        self.state.buf.len() - self.pending
+    }
+
+    fn as_ptr(&self) -> *const Self::Item {
+        unsafe { self.state.buf.as_ptr().add(self.pending) }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut Self::Item {
+        unsafe { self.state.buf.as_mut_ptr().add(self.pending) }
+    }
+
+    unsafe fn set_len(&mut self, new_len: usize) {
+        self.state.buf.set_len(new_len + self.pending);
+    }
+
+    fn advance(self) -> Self::Result {
+        let state = self.state;
+
+        if state.invalid.is_none() {
+            state.out.clear();
+            match state.decoding.decode(&state.buf, &mut state.out) {
+                Ok(consumed) => {
+                    state.buf.drain(..consumed);
+                    state.offset += consumed;
+                }
+                Err(at) => state.invalid = Some(state.offset + at),
+            }
+            state.undelivered.extend_from_slice(state.out.as_bytes());
+        }
+
+        // 無効なシーケンスより前の出力を渡しきってから失敗する。
+        if let Some(offset) = state.invalid {
+            if state.undelivered.is_empty() {
+                return self.control.cancel(DecodeError::InvalidSequence { offset });
+            }
+        }
+
+        deliver(&mut state.undelivered, self.control)
+    }
+
+    fn cancel(self, err: E) -> Self::Result {
+        self.state.buf.truncate(self.pending);
+        self.control.cancel(DecodeError::Inner(err))
+    }
+}
+
+/// pass `undelivered` to `control` as far as the writer accepts, and keep the rest for the next load.
+fn deliver<C: SequenceControl<Item = u8>>(undelivered: &mut Vec<u8>, control: C) -> C::Result {
+    let mut writer = control.request_writer(undelivered.len());
+    let mut delivered = 0;
+    for &b in undelivered.iter() {
+        if writer.push_item(b).is_err() {
+            break;
+        }
+        delivered += 1;
+    }
+
+    undelivered.drain(..delivered);
+    writer.advance()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{iterator_source::IteratorSource, vec_control::load_all};
+
+    #[test]
+    fn deliver_output_larger_than_writer() {
+        let src = [b"caf\xE9".as_slice(), b"\xE0 la".as_slice()];
+
+        for limit in [1, 2, 3, usize::MAX] {
+            let mut decoder = Decoder::new(IteratorSource::new(src), Latin1);
+            let out = load_all(&mut decoder, 4, limit).unwrap();
+            assert_eq!(out, "caféà la".as_bytes());
+        }
+    }
+
+    #[test]
+    fn decode_utf16_split_at_every_byte() {
+        let text = "aΑあ😀b";
+        let bin: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+
+        for i in 0..bin.len() {
+            let (l, r) = bin.split_at(i);
+            let source = IteratorSource::new([l, r]);
+            let mut decoder = Decoder::new(source, Utf16::new(ByteOrder::LittleEndian));
+            let out = load_all(&mut decoder, 4, 3).unwrap();
+            assert_eq!(out, text.as_bytes());
+        }
+    }
+
+    #[test]
+    fn report_invalid_and_truncated_sequence() {
+        let bin = [0x61, 0x00, 0x00, 0xDC, 0x62, 0x00];
+        let (l, r) = bin.split_at(2);
+        let mut decoder = Decoder::new(
+            IteratorSource::new([l, r]),
+            Utf16::new(ByteOrder::LittleEndian),
+        );
+        let (err, out) = load_all(&mut decoder, 4, usize::MAX).unwrap_err();
+        assert!(matches!(err, DecodeError::InvalidSequence { offset: 2 }));
+        assert_eq!(out, b"a");
+
+        // 同じロードの無効なシーケンスより前の出力も失われない。
+        for limit in [1, usize::MAX] {
+            let bin = [0x61, 0x00, 0x62, 0x00, 0x00, 0xDC, 0x63, 0x00];
+            let mut decoder = Decoder::new(
+                IteratorSource::new([bin.as_slice()]),
+                Utf16::new(ByteOrder::LittleEndian),
+            );
+            let (err, out) = load_all(&mut decoder, 4, limit).unwrap_err();
+            assert!(matches!(err, DecodeError::InvalidSequence { offset: 4 }));
+            assert_eq!(out, b"ab");
+        }
+
+        let bin = [0x61, 0x00, 0x3D];
+        let mut decoder = Decoder::new(
+            IteratorSource::new([bin.as_slice()]),
+            Utf16::new(ByteOrder::LittleEndian),
+        );
+        let (err, out) = load_all(&mut decoder, 4, usize::MAX).unwrap_err();
+        assert!(matches!(err, DecodeError::InvalidSequence { offset: 2 }));
+        assert_eq!(out, b"a");
+    }
+}
@@ -0,0 +1,27 @@
+use super::Decode;
+
+/// ISO-8859-1. every byte is decoded into the char of the same code point.
+#[derive(Debug, Default)]
+pub struct Latin1;
+
+impl Decode for Latin1 {
+    fn decode(&mut self, src: &[u8], out: &mut String) -> Result<usize, usize> {
+        out.extend(src.iter().map(|&b| char::from(b)));
+        Ok(src.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_every_byte() {
+        let src: Vec<u8> = (0..=255).collect();
+        let mut out = String::new();
+
+        let consumed = Latin1.decode(&src, &mut out).unwrap();
+        assert_eq!(consumed, src.len());
+        assert!(out.chars().map(u32::from).eq(0..=255));
+    }
+}
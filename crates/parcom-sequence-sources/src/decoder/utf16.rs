@@ -0,0 +1,134 @@
+use super::Decode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+/// UTF-16. a leading BOM decides the byte order and is not decoded.
+#[derive(Debug)]
+pub struct Utf16 {
+    order: ByteOrder,
+    bom_checked: bool,
+}
+
+impl Utf16 {
+    /// `order` is used if the source has no BOM.
+    pub fn new(order: ByteOrder) -> Self {
+        Self {
+            order,
+            bom_checked: false,
+        }
+    }
+
+    pub fn order(&self) -> ByteOrder {
+        self.order
+    }
+
+    fn unit(&self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        match self.order {
+            ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+        }
+    }
+}
+
+impl Decode for Utf16 {
+    fn decode(&mut self, src: &[u8], out: &mut String) -> Result<usize, usize> {
+        let mut i = 0;
+
+        if !self.bom_checked {
+            if src.len() < 2 {
+                return Ok(0);
+            }
+
+            match [src[0], src[1]] {
+                [0xFF, 0xFE] => {
+                    self.order = ByteOrder::LittleEndian;
+                    i = 2;
+                }
+                [0xFE, 0xFF] => {
+                    self.order = ByteOrder::BigEndian;
+                    i = 2;
+                }
+                _ => (),
+            }
+            self.bom_checked = true;
+        }
+
+        while i + 2 <= src.len() {
+            let unit = self.unit(&src[i..]);
+            match unit {
+                0xD800..0xDC00 => {
+                    // 下位サロゲートが次のロードで読み込まれる場合がある。
+                    if i + 4 > src.len() {
+                        break;
+                    }
+
+                    let low = self.unit(&src[i + 2..]);
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(i);
+                    }
+
+                    let c = 0x10000 + (((unit as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00);
+                    out.push(char::from_u32(c).unwrap());
+                    i += 4;
+                }
+                0xDC00..0xE000 => return Err(i),
+                _ => {
+                    out.push(char::from_u32(unit as u32).unwrap());
+                    i += 2;
+                }
+            }
+        }
+
+        Ok(i)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode(text: &str, order: ByteOrder) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|u| match order {
+                ByteOrder::LittleEndian => u.to_le_bytes(),
+                ByteOrder::BigEndian => u.to_be_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_split_at_every_byte() {
+        let text = "\u{FEFF}aΑあ😀b";
+        let bin = encode(text, ByteOrder::BigEndian);
+
+        for i in 0..bin.len() {
+            let mut decoding = Utf16::new(ByteOrder::LittleEndian);
+            let mut out = String::new();
+            let (l, r) = bin.split_at(i);
+
+            let consumed = decoding.decode(l, &mut out).unwrap();
+            let mut rest = l[consumed..].to_vec();
+            rest.extend_from_slice(r);
+            let consumed = decoding.decode(&rest, &mut out).unwrap();
+
+            assert_eq!(consumed, rest.len());
+            assert_eq!(decoding.order(), ByteOrder::BigEndian);
+            assert_eq!(out, &text[3..]);
+        }
+    }
+
+    #[test]
+    fn report_offset_of_unpaired_surrogate() {
+        let mut bin = encode("ab", ByteOrder::LittleEndian);
+        bin.extend_from_slice(&0xDC00u16.to_le_bytes());
+
+        let mut decoding = Utf16::new(ByteOrder::LittleEndian);
+        let mut out = String::new();
+        assert_eq!(decoding.decode(&bin, &mut out), Err(4));
+    }
+}
@@ -1,2 +1,7 @@
+pub mod decoder;
 pub mod iterator_source;
+pub mod token_source;
 pub mod utf8_validator;
+
+#[cfg(test)]
+mod vec_control;
@@ -0,0 +1,270 @@
+use parcom_core::{
+    IterativeParserOnce, IterativeParserState, SegmentStream, Sequence, SequenceSegment,
+};
+use parcom_sequence_core::{BufferWriter, SequenceControl, SequenceSource};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+/// source which runs an iterative parser (a lexer) over `input` and emits its outputs as items.
+///
+/// the built sequence has `[T]` segments, so a second parser can consume the tokens.
+/// if the lexer stops before the end of `input`, the sequence fails with `TokenSourceError::Unlexed`.
+pub struct TokenSource<'p, T, E> {
+    /// 字句解析を最後まで進めるfuture。要求された数のトークンが揃うたびに中断する。
+    lexer: Pin<Box<dyn 'p + Future<Output = Result<(), TokenSourceError<E>>>>>,
+    tokens: Rc<RefCell<Tokens<T>>>,
+    finished: bool,
+    error: Option<TokenSourceError<E>>,
+}
+
+#[derive(Debug)]
+pub enum TokenSourceError<E> {
+    Lexer(E),
+    /// the lexer finished without reading the whole input.
+    Unlexed,
+}
+
+struct Tokens<T> {
+    queue: VecDeque<T>,
+    requested: usize,
+}
+
+impl<'p, T: 'p, E: 'p> TokenSource<'p, T, E> {
+    pub fn new<S, P>(lexer: P, input: S) -> Self
+    where
+        S: 'p + Sequence,
+        S::Segment: SequenceSegment<Length = S::Length>,
+        S::Length: Default + Ord,
+        P: 'p + IterativeParserOnce<S, Output = T, Error = E>,
+    {
+        let tokens = Rc::new(RefCell::new(Tokens {
+            queue: VecDeque::new(),
+            requested: 0,
+        }));
+
+        let sink = Rc::clone(&tokens);
+        let lexer = Box::pin(async move {
+            let mut state = lexer.parse_iterative_once();
+            let mut rest = input;
+
+            loop {
+                match state.parse_next(rest).await {
+                    Ok((Some(token), r)) => {
+                        rest = r;
+                        let full = {
+                            let mut tokens = sink.borrow_mut();
+                            tokens.queue.push_back(token);
+                            tokens.queue.len() >= tokens.requested
+                        };
+                        if full {
+                            Suspend { suspended: false }.await;
+                        }
+                    }
+                    Ok((None, mut r)) => {
+                        return if is_empty(&mut r).await {
+                            Ok(())
+                        } else {
+                            Err(TokenSourceError::Unlexed)
+                        };
+                    }
+                    Err((err, _)) => return Err(TokenSourceError::Lexer(err)),
+                }
+            }
+        });
+
+        Self {
+            lexer,
+            tokens,
+            finished: false,
+            error: None,
+        }
+    }
+}
+
+async fn is_empty<S>(input: &mut S) -> bool
+where
+    S: Sequence,
+    S::Segment: SequenceSegment<Length = S::Length>,
+    S::Length: Default + Ord,
+{
+    let mut segments = input.segments();
+    while let Some(segment) = segments.next(Default::default()).await {
+        if segment.len() > Default::default() {
+            return false;
+        }
+    }
+    true
+}
+
+impl<'p, T, E> SequenceSource for TokenSource<'p, T, E> {
+    type Item = T;
+    type Error = TokenSourceError<E>;
+
+    type Next<'a, C>
+        = Next<'a, 'p, T, E, C>
+    where
+        Self: 'a,
+        C: 'a + SequenceControl<Item = Self::Item, Error = Self::Error>;
+
+    fn next<'a, C>(&'a mut self, control: C, size_hint: usize) -> Self::Next<'a, C>
+    where
+        C: 'a + SequenceControl<Item = Self::Item, Error = Self::Error>,
+    {
+        self.tokens.borrow_mut().requested = size_hint.max(1);
+
+        Next {
+            source: self,
+            control: Some(control),
+        }
+    }
+}
+
+pub struct Next<'a, 'p, T, E, C> {
+    source: &'a mut TokenSource<'p, T, E>,
+    control: Option<C>,
+}
+
+impl<'a, 'p, T, E, C> Future for Next<'a, 'p, T, E, C>
+where
+    C: SequenceControl<Item = T, Error = TokenSourceError<E>>,
+{
+    type Output = C::Result;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `lexer`はBoxでpinされており、ほかにpinされるものはない。
+        let this = unsafe { self.get_unchecked_mut() };
+        let source = &mut *this.source;
+
+        let starving = {
+            let tokens = source.tokens.borrow();
+            tokens.queue.len() < tokens.requested
+        };
+        if !source.finished && starving {
+            match source.lexer.as_mut().poll(cx) {
+                Poll::Ready(result) => {
+                    source.finished = true;
+                    source.error = result.err();
+                }
+                Poll::Pending => {
+                    // トークンが揃わないままPendingになった場合は、入力を待っている。
+                    let tokens = source.tokens.borrow();
+                    if tokens.queue.len() < tokens.requested {
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+
+        let control = this
+            .control
+            .take()
+            .expect("`Next` was polled after completion.");
+        let mut tokens = source.tokens.borrow_mut();
+
+        // 字句解析に失敗した場合、読み込み済みのトークンを渡したあとでエラーを返す。
+        if tokens.queue.is_empty() {
+            return Poll::Ready(match source.error.take() {
+                Some(err) => control.cancel(err),
+                None => control.finish(),
+            });
+        }
+
+        let mut writer = control.request_writer(tokens.queue.len());
+        while let Some(token) = tokens.queue.pop_front() {
+            if let Err(token) = writer.push_item(token) {
+                // 書き込めなかったトークンは次のロードで渡す。
+                tokens.queue.push_front(token);
+                break;
+            }
+        }
+
+        Poll::Ready(writer.advance())
+    }
+}
+
+/// return `Pending` once without waking, to hand the tokens to `Next`.
+struct Suspend {
+    suspended: bool,
+}
+
+impl Future for Suspend {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.suspended {
+            Poll::Ready(())
+        } else {
+            self.suspended = true;
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vec_control::load_all;
+    use parcom_core::Parser;
+    use parcom_parsers::{
+        primitive::{any_char, any_item, the_item},
+        ParserExtension,
+    };
+    use parcom_util::error::{Fatal, Miss};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Token {
+        Digit(char),
+        Plus,
+    }
+
+    fn token(c: char) -> Result<Token, Miss<()>> {
+        match c {
+            '0'..='9' => Ok(Token::Digit(c)),
+            '+' => Ok(Token::Plus),
+            _ => Err(Miss(())),
+        }
+    }
+
+    #[test]
+    fn lex_then_parse_tokens() {
+        // 要求数より書き込める数が少ない場合も、トークンは失われない。
+        for (size_hint, limit) in [(1, usize::MAX), (4, 2), (16, 3)] {
+            let lexer = any_char().and_then(token).repeat();
+            let mut source = TokenSource::new(lexer, "1+2+3");
+            let tokens = load_all(&mut source, size_hint, limit).ok().unwrap();
+            assert_eq!(tokens.len(), 5);
+
+            let sum = any_item().join(the_item(Token::Plus).join(any_item()).repeat());
+            let ((_, (terms, _)), rest) = pollster::block_on(sum.parse(tokens.as_slice()))
+                .ok()
+                .unwrap();
+            assert_eq!(terms.len(), 2);
+            assert!(rest.is_empty());
+        }
+    }
+
+    #[test]
+    fn emit_tokens_before_error() {
+        let lexer = any_char().and_then(token).map_err(Fatal).repeat();
+        let mut source = TokenSource::new(lexer, "12x");
+        let Err((TokenSourceError::Lexer(_), tokens)) = load_all(&mut source, 16, usize::MAX)
+        else {
+            panic!()
+        };
+        assert_eq!(tokens, [Token::Digit('1'), Token::Digit('2')]);
+
+        // 字句解析が入力の途中で終わった場合も失敗する。
+        let lexer = any_char().and_then(token).repeat();
+        let mut source = TokenSource::new(lexer, "1+x");
+        let Err((TokenSourceError::Unlexed, tokens)) = load_all(&mut source, 16, usize::MAX) else {
+            panic!()
+        };
+        assert_eq!(tokens, [Token::Digit('1'), Token::Plus]);
+    }
+}
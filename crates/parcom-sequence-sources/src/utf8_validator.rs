@@ -2,37 +2,130 @@ use parcom_sequence_core::{BufferWriter, SequenceControl, SequenceSource};
 use std::{
     future::{Future, IntoFuture},
     marker::PhantomData,
+    ops::Range,
     pin::Pin,
     task::Poll,
 };
 
-pub struct Utf8Validator<S: SequenceSource<Item = u8>> {
+const REPLACEMENT: &[u8] = "\u{FFFD}".as_bytes();
+
+pub struct Utf8Validator<S: SequenceSource<Item = u8>, H: InvalidSequenceHandler = Strict> {
     buffered: usize,
     rest: [u8; 3],
+    /// byte offset in the source of the head of `rest`.
+    offset: usize,
+    /// replacement bytes which the control could not accept yet.
+    undelivered: Vec<u8>,
+    handler: H,
     source: S,
 }
 
-impl<S: SequenceSource<Item = u8>> Utf8Validator<S> {
+impl<S: SequenceSource<Item = u8>> Utf8Validator<S, Strict> {
     pub fn new(source: S) -> Self {
+        Self::with_handler(source, Strict)
+    }
+}
+
+impl<S: SequenceSource<Item = u8>> Utf8Validator<S, Lossy> {
+    /// replace invalid sequences with U+FFFD in the same way as `String::from_utf8_lossy`.
+    pub fn lossy(source: S) -> Self {
+        Self::with_handler(source, Lossy)
+    }
+}
+
+impl<S: SequenceSource<Item = u8>, H: InvalidSequenceHandler> Utf8Validator<S, H> {
+    pub fn with_handler(source: S, handler: H) -> Self {
         Self {
             buffered: 0,
             rest: [0; 3],
+            offset: 0,
+            undelivered: Vec::new(),
+            handler,
             source,
         }
     }
 }
 
+/// decide how `Utf8Validator` treats invalid sequences.
+pub trait InvalidSequenceHandler {
+    /// if true, each invalid sequence is replaced with U+FFFD.
+    /// otherwise, the stream is aborted with `Utf8ValidationError::InvalidSequence`,
+    /// except that a truncated sequence at the end of the source is dropped.
+    fn replace(&self) -> bool;
+
+    /// called with the range of each invalid sequence in source byte offsets.
+    fn report(&mut self, range: Range<usize>) {
+        let _ = range;
+    }
+}
+
+/// abort the stream at the first invalid sequence, and drop a truncated sequence at the end.
+pub struct Strict;
+
+impl InvalidSequenceHandler for Strict {
+    fn replace(&self) -> bool {
+        false
+    }
+}
+
+/// replace invalid sequences with U+FFFD.
+pub struct Lossy;
+
+impl InvalidSequenceHandler for Lossy {
+    fn replace(&self) -> bool {
+        true
+    }
+}
+
+/// report the range of each invalid sequence to the callback.
+pub struct Report<F> {
+    callback: F,
+    replace: bool,
+}
+
+impl<F: FnMut(Range<usize>)> Report<F> {
+    /// abort the stream at the first invalid sequence after reporting it.
+    /// a truncated sequence at the end is reported and dropped.
+    pub fn strict(callback: F) -> Self {
+        Self {
+            callback,
+            replace: false,
+        }
+    }
+
+    /// replace invalid sequences with U+FFFD.
+    pub fn lossy(callback: F) -> Self {
+        Self {
+            callback,
+            replace: true,
+        }
+    }
+}
+
+impl<F: FnMut(Range<usize>)> InvalidSequenceHandler for Report<F> {
+    fn replace(&self) -> bool {
+        self.replace
+    }
+
+    fn report(&mut self, range: Range<usize>) {
+        (self.callback)(range)
+    }
+}
+
+#[derive(Debug)]
 pub enum Utf8ValidationError<E> {
     InvalidSequence,
     Inner(E),
 }
 
-impl<S: SequenceSource<Item = u8>> SequenceSource for Utf8Validator<S> {
+impl<S: SequenceSource<Item = u8>, H: InvalidSequenceHandler> SequenceSource
+    for Utf8Validator<S, H>
+{
     type Item = u8;
     type Error = Utf8ValidationError<S::Error>;
 
     type Next<'a, C>
-        = Next<'a, S, C>
+        = Next<'a, S, C, H>
     where
         Self: 'a,
         C: 'a + SequenceControl<Item = Self::Item, Error = Self::Error>;
@@ -41,9 +134,12 @@ impl<S: SequenceSource<Item = u8>> SequenceSource for Utf8Validator<S> {
     where
         C: 'a + SequenceControl<Item = Self::Item, Error = Self::Error>,
     {
-        let control = Control::<S, C> {
+        let control = Control::<S, C, H> {
             buffered: self.buffered,
             rest: self.rest,
+            offset: self.offset,
+            undelivered: &mut self.undelivered,
+            handler: &mut self.handler,
             control,
             _phantom: PhantomData,
         };
@@ -53,6 +149,7 @@ impl<S: SequenceSource<Item = u8>> SequenceSource for Utf8Validator<S> {
         Next {
             buffered: &mut self.buffered,
             rest: &mut self.rest,
+            offset: &mut self.offset,
             fut,
         }
     }
@@ -62,16 +159,19 @@ pub struct Next<
     'a,
     S: SequenceSource<Item = u8> + 'a,
     C: 'a + SequenceControl<Item = u8, Error = Utf8ValidationError<S::Error>>,
+    H: 'a + InvalidSequenceHandler,
 > {
     buffered: &'a mut usize,
     rest: &'a mut [u8; 3],
-    fut: <S::Next<'a, Control<S, C>> as IntoFuture>::IntoFuture,
+    offset: &'a mut usize,
+    fut: <S::Next<'a, Control<'a, S, C, H>> as IntoFuture>::IntoFuture,
 }
 
-impl<'a, S, C> Future for Next<'a, S, C>
+impl<'a, S, C, H> Future for Next<'a, S, C, H>
 where
     S: SequenceSource<Item = u8>,
     C: SequenceControl<Item = u8, Error = Utf8ValidationError<S::Error>>,
+    H: InvalidSequenceHandler,
 {
     type Output = C::Result;
 
@@ -84,10 +184,12 @@ where
                 InnerResponse::Advance {
                     buffered,
                     rest,
+                    offset,
                     res,
                 } => {
                     *this.buffered = buffered;
                     *this.rest = rest;
+                    *this.offset = offset;
 
                     Poll::Ready(res)
                 }
@@ -99,25 +201,36 @@ where
     }
 }
 
-struct Control<S: SequenceSource<Item = u8>, C: SequenceControl> {
+struct Control<'a, S: SequenceSource<Item = u8>, C: SequenceControl, H> {
     buffered: usize,
     rest: [u8; 3],
+    offset: usize,
+    undelivered: &'a mut Vec<u8>,
+    handler: &'a mut H,
     control: C,
     _phantom: PhantomData<fn(S) -> S>,
 }
 
-impl<S, C> SequenceControl for Control<S, C>
+impl<'a, S, C, H> SequenceControl for Control<'a, S, C, H>
 where
     S: SequenceSource<Item = u8>,
     C: SequenceControl<Item = u8, Error = Utf8ValidationError<S::Error>>,
+    H: InvalidSequenceHandler,
 {
     type Item = u8;
     type Error = S::Error;
     type Result = Response<S, C>;
-    type Writer = Writer<S, C>;
+    type Writer = Writer<'a, S, C, H>;
 
     fn request_writer(self, min_capacity: usize) -> Self::Writer {
-        let mut req = self.control.request_writer(self.buffered + min_capacity);
+        // 置換によって無効なバイト列は最大3倍に伸びる。
+        let capacity = if self.handler.replace() {
+            (self.buffered + min_capacity) * REPLACEMENT.len()
+        } else {
+            self.buffered + min_capacity
+        };
+
+        let mut req = self.control.request_writer(capacity);
         let dst = req.as_mut_ptr();
         let src = self.rest.as_ptr();
 
@@ -128,6 +241,8 @@ where
 
         Writer {
             buffered: self.buffered,
+            offset: self.offset,
+            handler: self.handler,
             req,
             _phantom: PhantomData,
         }
@@ -142,28 +257,84 @@ where
     }
 
     fn finish(self) -> Self::Result {
-        let res = self.control.finish();
+        // 渡しきれていない置換文字があれば、終了する前に渡す。
+        if !self.undelivered.is_empty() {
+            let res = deliver(self.undelivered, self.control);
+            return Response {
+                inner: InnerResponse::Advance {
+                    buffered: self.buffered,
+                    rest: self.rest,
+                    offset: self.offset,
+                    res,
+                },
+                _phantom: PhantomData,
+            };
+        }
+
+        if self.buffered == 0 {
+            let res = self.control.finish();
+            return Response {
+                inner: InnerResponse::Finish { res },
+                _phantom: PhantomData,
+            };
+        }
+
+        // 末尾の不完全なバイト列は無効なシーケンスとして扱う。置換しない場合は従来どおり捨てて終了する。
+        let range = self.offset..self.offset + self.buffered;
+        self.handler.report(range.clone());
+
+        let inner = if self.handler.replace() {
+            self.undelivered.extend_from_slice(REPLACEMENT);
+            InnerResponse::Advance {
+                buffered: 0,
+                rest: [0; 3],
+                offset: range.end,
+                res: deliver(self.undelivered, self.control),
+            }
+        } else {
+            let res = self.control.finish();
+            InnerResponse::Finish { res }
+        };
+
         Response {
-            inner: InnerResponse::Finish { res },
+            inner,
             _phantom: PhantomData,
         }
     }
 }
 
-struct Writer<S, C>
+/// pass `undelivered` to `control` as far as the writer accepts, and keep the rest for the next load.
+fn deliver<C: SequenceControl<Item = u8>>(undelivered: &mut Vec<u8>, control: C) -> C::Result {
+    let mut writer = control.request_writer(undelivered.len());
+    let mut delivered = 0;
+    for &b in undelivered.iter() {
+        if writer.push_item(b).is_err() {
+            break;
+        }
+        delivered += 1;
+    }
+
+    undelivered.drain(..delivered);
+    writer.advance()
+}
+
+struct Writer<'a, S, C, H>
 where
     S: SequenceSource<Item = u8>,
     C: SequenceControl<Item = u8, Error = Utf8ValidationError<S::Error>>,
 {
     buffered: usize,
+    offset: usize,
+    handler: &'a mut H,
     req: C::Writer,
     _phantom: PhantomData<fn(S) -> S>,
 }
 
-impl<S, C> BufferWriter for Writer<S, C>
+impl<'a, S, C, H> BufferWriter for Writer<'a, S, C, H>
 where
     S: SequenceSource<Item = u8>,
     C: SequenceControl<Item = u8, Error = Utf8ValidationError<S::Error>>,
+    H: InvalidSequenceHandler,
 {
     type Segment = str;
     type Item = u8;
@@ -171,7 +342,11 @@ where
     type Result = Response<S, C>;
 
     fn capacity(&self) -> usize {
-        self.req.capacity() - self.buffered
+        if self.handler.replace() {
+            (self.req.capacity() / REPLACEMENT.len()).saturating_sub(self.buffered)
+        } else {
+            self.req.capacity() - self.buffered
+        }
     }
 
     fn len(&self) -> usize {
@@ -192,33 +367,66 @@ where
 
     fn advance(mut self) -> Response<S, C> {
         let buf = self.req.as_slice();
+        let len = buf.len();
+
+        // 無効なシーケンスを置換した場合のみ出力を作り直す。
+        let mut replaced: Option<Vec<u8>> = None;
+        let mut pos = 0;
+        let valid_len = loop {
+            let e = match std::str::from_utf8(&buf[pos..]) {
+                Ok(_) => break len,
+                Err(e) => e,
+            };
+
+            let valid_end = pos + e.valid_up_to();
+            let Some(error_len) = e.error_len() else {
+                // 後続のバイトで完成する可能性があるため持ち越す。
+                break valid_end;
+            };
+
+            let start = self.offset + valid_end;
+            self.handler.report(start..start + error_len);
+
+            if !self.handler.replace() {
+                let res = self.req.cancel(Utf8ValidationError::InvalidSequence);
+                return Response {
+                    inner: InnerResponse::Err { res },
+                    _phantom: PhantomData,
+                };
+            }
 
-        let valid_len = match std::str::from_utf8(buf) {
-            Ok(_) => buf.len(),
-            Err(e) => e.valid_up_to(),
+            let out = replaced.get_or_insert_with(|| Vec::with_capacity(len));
+            out.extend_from_slice(&buf[pos..valid_end]);
+            out.extend_from_slice(REPLACEMENT);
+            pos = valid_end + error_len;
         };
 
         let invalids = &buf[valid_len..];
+        let mut rest = [0; 3];
+        rest[..invalids.len()].copy_from_slice(invalids);
+        let buffered = invalids.len();
+        let offset = self.offset + valid_len;
+
+        match replaced {
+            Some(mut out) => {
+                out.extend_from_slice(&buf[pos..valid_len]);
+
+                // SAFETY: `capacity` の制限により置換後の長さは `req` の容量を超えない。
+                unsafe {
+                    std::ptr::copy_nonoverlapping(out.as_ptr(), self.req.as_mut_ptr(), out.len());
+                    self.req.set_len(out.len());
+                }
+            }
+            None => self.req.shrink_to(valid_len),
+        }
 
-        let inner = if invalids.len() > 3 {
-            let res = self.req.cancel(Utf8ValidationError::InvalidSequence);
-            InnerResponse::Err { res }
-        } else {
-            let mut rest = [0; 3];
-            rest[..invalids.len()].copy_from_slice(invalids);
-
-            let buffered = invalids.len();
-            self.req.shrink_to(valid_len);
-
-            InnerResponse::Advance {
+        Response {
+            inner: InnerResponse::Advance {
                 buffered,
                 rest,
+                offset,
                 res: self.req.advance(),
-            }
-        };
-
-        Response {
-            inner,
+            },
             _phantom: PhantomData,
         }
     }
@@ -249,6 +457,7 @@ where
     Advance {
         buffered: usize,
         rest: [u8; 3],
+        offset: usize,
         res: C::Result,
     },
     Finish {
@@ -260,50 +469,125 @@ where
 }
 
 #[cfg(test)]
-
 mod test {
-    // TODO: tests/配下に移動
-    // use super::*;
-    // use crate::{control::vec_control::VecControl, source::iterator_source::IteratorSource};
-
-    // #[test]
-    // fn test_valid() {
-    //     // "a": 1 byte
-    //     // "Α": 2 byte
-    //     // "あ": 3 byte
-    //     // "😀": 4 byte
-    //     //
-    //     // バイト長ごとに隣接したパターンをすべて確認する。
-    //     // 隣接パターン: 11, 12, 13, 14, 21, 22, 23, 24, 31, 32, 33, 34, 41, 42, 43, 44
-    //     // 上の隣接パターンをすべてもつ列: 11213142232433441 をテストに使う。
-    //     let text = "aaΑaあa😀ΑΑあΑ😀ああ😀😀a";
-    //     let bin = text.as_bytes();
-
-    //     for i in 0..bin.len() {
-    //         let (l, r) = bin.split_at(i);
-    //         let src = IteratorSource::new([l, r]);
-    //         let mut src = Utf8Validator::new(src);
-    //         let mut buf = Vec::new();
-
-    //         loop {
-    //             let control = VecControl::new(&mut buf);
-
-    //             let res = pollster::block_on(src.next(control, 0));
-
-    //             match res {
-    //                 crate::control::Response::Advance(_) => {
-    //                     let r = std::str::from_utf8(&buf).unwrap();
-    //                     assert!(text.starts_with(r));
-    //                 }
-    //                 crate::control::Response::Finish(_) => {
-    //                     let r = std::str::from_utf8(&buf).unwrap();
-    //                     assert!(text.starts_with(r));
-    //                     assert_eq!(r, text);
-    //                     break;
-    //                 }
-    //                 _ => unreachable!(),
-    //             }
-    //         }
-    //     }
-    // }
+    use super::*;
+    use crate::{
+        iterator_source::IteratorSource,
+        vec_control::{load_all, Response, VecControl},
+    };
+
+    #[test]
+    fn test_valid() {
+        // "a": 1 byte
+        // "Α": 2 byte
+        // "あ": 3 byte
+        // "😀": 4 byte
+        //
+        // バイト長ごとに隣接したパターンをすべて確認する。
+        // 隣接パターン: 11, 12, 13, 14, 21, 22, 23, 24, 31, 32, 33, 34, 41, 42, 43, 44
+        // 上の隣接パターンをすべてもつ列: 11213142232433441 をテストに使う。
+        let text = "aaΑaあa😀ΑΑあΑ😀ああ😀😀a";
+        let bin = text.as_bytes();
+
+        for i in 0..bin.len() {
+            let (l, r) = bin.split_at(i);
+            let src = IteratorSource::new([l, r]);
+            let mut src = Utf8Validator::new(src);
+            let mut buf = Vec::new();
+
+            loop {
+                let control = VecControl::new(&mut buf);
+
+                let res = pollster::block_on(src.next(control, 0));
+
+                match res {
+                    Response::Advance(_) => {
+                        let r = std::str::from_utf8(&buf).unwrap();
+                        assert!(text.starts_with(r));
+                    }
+                    Response::Finish => {
+                        let r = std::str::from_utf8(&buf).unwrap();
+                        assert_eq!(r, text);
+                        break;
+                    }
+                    Response::Cancel(_) => unreachable!(),
+                }
+            }
+        }
+    }
+
+    // 無効なバイト列と末尾の不完全なバイト列をもつ入力をあらゆる位置で分割する。
+    const INVALID: &[u8] = b"a\xE3\x81b\xFF\xF0\x9F\x98\x80\xF0\x9F";
+
+    fn split_at_every_byte() -> impl Iterator<Item = [&'static [u8]; 2]> {
+        (0..=INVALID.len()).map(|i| {
+            let (l, r) = INVALID.split_at(i);
+            [l, r]
+        })
+    }
+
+    #[test]
+    fn strict_abort_at_invalid_sequence() {
+        for src in split_at_every_byte() {
+            let mut src = Utf8Validator::new(IteratorSource::new(src));
+            let (err, out) = load_all(&mut src, 4, usize::MAX).unwrap_err();
+            assert!(matches!(err, Utf8ValidationError::InvalidSequence));
+            assert!(out.is_empty() || out == b"a");
+        }
+
+        // 末尾の不完全なバイト列は捨てて終了する。
+        let mut src = Utf8Validator::new(IteratorSource::new([b"a\xF0".as_slice(), b"\x9F"]));
+        assert_eq!(load_all(&mut src, 4, usize::MAX).unwrap(), b"a");
+    }
+
+    #[test]
+    fn lossy_replace_invalid_sequences() {
+        let expected = String::from_utf8_lossy(INVALID);
+
+        for src in split_at_every_byte() {
+            let mut src = Utf8Validator::lossy(IteratorSource::new(src));
+            let out = load_all(&mut src, 4, usize::MAX).unwrap();
+            assert_eq!(std::str::from_utf8(&out).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn deliver_trailing_replacement_to_small_writer() {
+        let mut src = Utf8Validator::lossy(IteratorSource::new([b"a\xF0\x9F".as_slice()]));
+        let mut buf = Vec::new();
+        let res = pollster::block_on(src.next(VecControl::new(&mut buf), 4));
+        assert!(matches!(res, Response::Advance(1)));
+
+        // 置換文字が1バイトずつしか書き込めなくても、分断されずにすべて渡される。
+        loop {
+            let control = VecControl::with_limit(&mut buf, 1);
+            match pollster::block_on(src.next(control, 4)) {
+                Response::Advance(_) => (),
+                Response::Finish => break,
+                Response::Cancel(_) => unreachable!(),
+            }
+        }
+        assert_eq!(buf, "a\u{FFFD}".as_bytes());
+    }
+
+    #[test]
+    fn report_ranges_of_invalid_sequences() {
+        for src in split_at_every_byte() {
+            let mut ranges = Vec::new();
+            let handler = Report::lossy(|range| ranges.push(range));
+            let mut src = Utf8Validator::with_handler(IteratorSource::new(src), handler);
+            load_all(&mut src, 4, usize::MAX).unwrap();
+            drop(src);
+            assert_eq!(ranges, [1..3, 4..5, 9..11]);
+        }
+
+        for src in split_at_every_byte() {
+            let mut ranges = Vec::new();
+            let handler = Report::strict(|range| ranges.push(range));
+            let mut src = Utf8Validator::with_handler(IteratorSource::new(src), handler);
+            assert!(load_all(&mut src, 4, usize::MAX).is_err());
+            drop(src);
+            assert_eq!(ranges, [1..3]);
+        }
+    }
 }
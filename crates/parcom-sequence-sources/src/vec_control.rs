@@ -0,0 +1,125 @@
+use parcom_sequence_core::{BufferWriter, SequenceControl, SequenceSource};
+use std::marker::PhantomData;
+
+/// control which appends the loaded items to a `Vec`.
+pub struct VecControl<'a, T, E> {
+    buf: &'a mut Vec<T>,
+    limit: usize,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<'a, T, E> VecControl<'a, T, E> {
+    pub fn new(buf: &'a mut Vec<T>) -> Self {
+        Self::with_limit(buf, usize::MAX)
+    }
+
+    /// give writers at most `limit` items of capacity regardless of the request.
+    pub fn with_limit(buf: &'a mut Vec<T>, limit: usize) -> Self {
+        Self {
+            buf,
+            limit,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Response<E> {
+    /// count of the appended items.
+    Advance(usize),
+    Finish,
+    Cancel(E),
+}
+
+impl<'a, T, E> SequenceControl for VecControl<'a, T, E> {
+    type Item = T;
+    type Result = Response<E>;
+    type Error = E;
+    type Writer = VecWriter<'a, T, E>;
+
+    fn request_writer(self, byte_length: usize) -> Self::Writer {
+        let capacity = byte_length.min(self.limit);
+        self.buf.reserve(capacity);
+
+        VecWriter {
+            offset: self.buf.len(),
+            capacity,
+            buf: self.buf,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn cancel(self, err: Self::Error) -> Self::Result {
+        Response::Cancel(err)
+    }
+
+    fn finish(self) -> Self::Result {
+        Response::Finish
+    }
+}
+
+pub struct VecWriter<'a, T, E> {
+    buf: &'a mut Vec<T>,
+    offset: usize,
+    capacity: usize,
+    _phantom: PhantomData<fn() -> E>,
+}
+
+impl<'a, T, E> BufferWriter for VecWriter<'a, T, E> {
+    type Segment = [T];
+    type Item = T;
+    type Result = Response<E>;
+    type Error = E;
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn len(&self) -> usize {
+        self.buf.len() - self.offset
+    }
+
+    fn as_ptr(&self) -> *const Self::Item {
+        unsafe { self.buf.as_ptr().add(self.offset) }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut Self::Item {
+        unsafe { self.buf.as_mut_ptr().add(self.offset) }
+    }
+
+    unsafe fn set_len(&mut self, new_len: usize) {
+        self.buf.set_len(new_len + self.offset);
+    }
+
+    fn advance(self) -> Self::Result {
+        Response::Advance(self.len())
+    }
+
+    fn cancel(self, err: Self::Error) -> Self::Result {
+        self.buf.truncate(self.offset);
+        Response::Cancel(err)
+    }
+}
+
+/// the loaded items, or the error with the items loaded before it.
+pub type Loaded<S> = Result<
+    Vec<<S as SequenceSource>::Item>,
+    (
+        <S as SequenceSource>::Error,
+        Vec<<S as SequenceSource>::Item>,
+    ),
+>;
+
+/// load all items of `source`, requesting `size_hint` items at each load.
+pub fn load_all<S: SequenceSource>(source: &mut S, size_hint: usize, limit: usize) -> Loaded<S> {
+    let mut buf = Vec::new();
+
+    loop {
+        let control = VecControl::with_limit(&mut buf, limit);
+        match pollster::block_on(source.next(control, size_hint)) {
+            Response::Advance(_) => (),
+            Response::Finish => return Ok(buf),
+            Response::Cancel(e) => return Err((e, buf)),
+        }
+    }
+}
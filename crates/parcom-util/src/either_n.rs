@@ -0,0 +1,44 @@
+use parcom_core::{ParseError, ShouldNever};
+
+macro_rules! either_n {
+    ($($name: ident { $($variant: ident $t: ident),* })*) => {
+        $(
+            /// flat counterpart of nested `Either`s. `V{i}` holds a value of the `i`-th type.
+            #[derive(Debug, Clone)]
+            pub enum $name<$($t),*> {
+                $($variant($t)),*
+            }
+
+            impl<$($t),*> $name<$($t),*> {
+                pub fn unify<T>(self) -> T
+                where
+                    $($t: Into<T>),*
+                {
+                    match self {
+                        $(Self::$variant(e) => e.into()),*
+                    }
+                }
+            }
+
+            unsafe impl<$($t: ShouldNever),*> ShouldNever for $name<$($t),*> {}
+
+            impl<$($t: ParseError),*> ParseError for $name<$($t),*> {
+                fn should_terminate(&self) -> bool {
+                    match self {
+                        $(Self::$variant(e) => e.should_terminate()),*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+either_n! {
+    Either2 { V0 T0, V1 T1 }
+    Either3 { V0 T0, V1 T1, V2 T2 }
+    Either4 { V0 T0, V1 T1, V2 T2, V3 T3 }
+    Either5 { V0 T0, V1 T1, V2 T2, V3 T3, V4 T4 }
+    Either6 { V0 T0, V1 T1, V2 T2, V3 T3, V4 T4, V5 T5 }
+    Either7 { V0 T0, V1 T1, V2 T2, V3 T3, V4 T4, V5 T5, V6 T6 }
+    Either8 { V0 T0, V1 T1, V2 T2, V3 T3, V4 T4, V5 T5, V6 T6, V7 T7 }
+}
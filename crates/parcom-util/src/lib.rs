@@ -1,5 +1,6 @@
 mod either;
 mod either_both;
+mod either_n;
 
 pub mod error;
 
@@ -7,6 +8,7 @@ use parcom_core::{ParseError, ParseResult, Sequence, UnknownLocation};
 
 pub use either::Either;
 pub use either_both::EitherBoth;
+pub use either_n::{Either2, Either3, Either4, Either5, Either6, Either7, Either8};
 
 pub fn done<S: Sequence, O, E: ParseError>(output: O, rest: S) -> ParseResult<S, O, E> {
     Ok((output, rest))